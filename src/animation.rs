@@ -0,0 +1,147 @@
+use crate::transform::PixelArray;
+
+pub mod export;
+pub mod sheet;
+
+pub const DEFAULT_DURATION: u32 = 100;
+
+pub struct Frame {
+    pub pixels: PixelArray,
+    /// How long the frame is shown for, in milliseconds
+    pub duration: u32,
+}
+
+impl Default for Frame {
+    fn default() -> Self {
+        Self {
+            pixels: PixelArray::default(),
+            duration: DEFAULT_DURATION,
+        }
+    }
+}
+
+/// Every frame of the drawing.
+///
+/// The current frame's pixels are kept in the store's `PixelArray` so the tools don't need to know about frames.
+/// Its slot here holds an empty array until another frame is selected, so anything reading every frame takes the active pixels too.
+pub struct Animation {
+    frames: Vec<Frame>,
+    current: usize,
+}
+
+impl Default for Animation {
+    fn default() -> Self {
+        Self {
+            frames: vec![Frame::default()],
+            current: 0,
+        }
+    }
+}
+
+impl Animation {
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    pub fn current(&self) -> usize {
+        self.current
+    }
+
+    pub fn duration(&self, index: usize) -> u32 {
+        self.frames[index].duration
+    }
+
+    pub fn set_duration(&mut self, index: usize, duration: u32) {
+        self.frames[index].duration = duration.max(1);
+    }
+
+    /// Total length of one loop, in milliseconds
+    pub fn total_duration(&self) -> u32 {
+        self.frames.iter().map(|frame| frame.duration).sum()
+    }
+
+    /// The frame shown `time` milliseconds into looping playback
+    pub fn frame_at(&self, time: u32) -> usize {
+        let mut time = time % self.total_duration().max(1);
+        for (index, frame) in self.frames.iter().enumerate() {
+            if time < frame.duration {
+                return index;
+            }
+            time -= frame.duration;
+        }
+        self.frames.len() - 1
+    }
+
+    /// Makes `index` the current frame, swapping its pixels with `active`
+    pub fn select(&mut self, index: usize, active: &mut PixelArray) {
+        if index == self.current || index >= self.frames.len() {
+            return;
+        }
+
+        std::mem::swap(active, &mut self.frames[self.current].pixels);
+        std::mem::swap(active, &mut self.frames[index].pixels);
+        self.current = index;
+    }
+
+    /// Adds a frame after the current one and selects it, either empty or a copy of `active`
+    pub fn insert(&mut self, active: &mut PixelArray, duplicate: bool) {
+        let frame = Frame {
+            pixels: if duplicate { active.clone() } else { PixelArray::default() },
+            duration: self.frames[self.current].duration,
+        };
+        self.frames.insert(self.current + 1, frame);
+        self.select(self.current + 1, active);
+    }
+
+    /// Removes the current frame, selecting the one after it if there is one. The last frame is only cleared
+    pub fn remove(&mut self, active: &mut PixelArray) {
+        if self.frames.len() == 1 {
+            *active = PixelArray::default();
+            return;
+        }
+
+        let removed = self.current;
+        let next = if removed + 1 < self.frames.len() { removed + 1 } else { removed - 1 };
+        self.select(next, active);
+        self.frames.remove(removed);
+        if next > removed {
+            self.current -= 1;
+        }
+    }
+
+    /// Adds `frames` after the current one and selects the first of them
+    pub fn append(&mut self, frames: Vec<Frame>, active: &mut PixelArray) {
+        if frames.is_empty() {
+            return;
+        }
+        let index = self.current + 1;
+        self.frames.splice(index..index, frames);
+        self.select(index, active);
+    }
+
+    /// Swaps the current frame with its neighbour, `-1` for the previous one and `1` for the next
+    pub fn shift(&mut self, offset: isize) {
+        let Some(index) = self.current.checked_add_signed(offset).filter(|&d| d < self.frames.len()) else { return };
+        self.frames.swap(self.current, index);
+        self.current = index;
+    }
+
+    /// The frames either side of the current one, for onion skinning
+    pub fn neighbours(&self) -> [Option<&PixelArray>; 2] {
+        [
+            self.current.checked_sub(1).map(|index| &self.frames[index].pixels),
+            self.frames.get(self.current + 1).map(|frame| &frame.pixels),
+        ]
+    }
+
+    /// Every frame's pixels and duration in order, with `active` standing in for the current frame
+    pub fn frames<'a>(&'a self, active: &'a PixelArray) -> impl Iterator<Item = (&'a PixelArray, u32)> {
+        self.frames.iter().enumerate().map(move |(index, frame)| {
+            (if index == self.current { active } else { &frame.pixels }, frame.duration)
+        })
+    }
+}
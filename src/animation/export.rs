@@ -0,0 +1,102 @@
+use std::{fs::File, io::BufWriter, path::Path};
+
+use image::{Delay, Frame, RgbaImage, codecs::gif::{GifEncoder, Repeat}};
+
+use super::sheet::{self, SheetLayout};
+use crate::transform::PixelArray;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AnimationFormat {
+    Gif,
+    Apng,
+    SpriteSheet(SheetLayout),
+}
+
+impl AnimationFormat {
+    pub const ALL: [AnimationFormat; 4] = [Self::Gif, Self::Apng, Self::SpriteSheet(SheetLayout::Grid), Self::SpriteSheet(SheetLayout::Packed)];
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Gif => "gif",
+            Self::Apng | Self::SpriteSheet(_) => "png",
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Gif => "GIF",
+            Self::Apng => "APNG",
+            Self::SpriteSheet(SheetLayout::Grid) => "Sheet (grid)",
+            Self::SpriteSheet(SheetLayout::Packed) => "Sheet (packed)",
+        }
+    }
+}
+
+fn to_u8(value: f32) -> u8 {
+    (value.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+/// Top left corner and size of the area covering every frame
+fn bounds<'a>(frames: impl IntoIterator<Item = &'a PixelArray>) -> ([i16; 2], [u32; 2]) {
+    let mut min = [i16::MAX; 2];
+    let mut max = [i16::MIN; 2];
+    for pixel in frames.into_iter().flat_map(|pixels| pixels.iter()) {
+        for axis in 0..2 {
+            min[axis] = min[axis].min(pixel.pos[axis]);
+            max[axis] = max[axis].max(pixel.pos[axis]);
+        }
+    }
+
+    if min[0] > max[0] {
+        return ([0, 0], [1, 1]);
+    }
+    (min, [(max[0] as i32 - min[0] as i32 + 1) as u32, (max[1] as i32 - min[1] as i32 + 1) as u32])
+}
+
+fn render(pixels: &PixelArray, min: [i16; 2], size: [u32; 2]) -> RgbaImage {
+    let mut image = RgbaImage::new(size[0], size[1]);
+    for pixel in pixels.iter() {
+        let x = (pixel.pos[0] as i32 - min[0] as i32) as u32;
+        let y = (pixel.pos[1] as i32 - min[1] as i32) as u32;
+        image.put_pixel(x, y, image::Rgba(pixel.col.map(to_u8)));
+    }
+    image
+}
+
+/// Writes every frame to `path`, each cropped to the same area so they line up.
+/// `frames` are the pixels of each frame with how long it's shown for in milliseconds.
+pub fn export(path: &Path, format: AnimationFormat, frames: &[(&PixelArray, u32)]) -> Result<(), String> {
+    let (min, size) = bounds(frames.iter().map(|(pixels, _)| *pixels));
+    let images = frames.iter().map(|(pixels, duration)| (render(pixels, min, size), *duration)).collect::<Vec<_>>();
+
+    let result = match format {
+        AnimationFormat::Gif => write_gif(path, images),
+        AnimationFormat::Apng => write_apng(path, images, size),
+        AnimationFormat::SpriteSheet(layout) => sheet::write(path, images, layout),
+    };
+    result.map_err(|err| format!("Error writing `{}`:\n\n{err}", path.display()))
+}
+
+fn write_gif(path: &Path, images: Vec<(RgbaImage, u32)>) -> Result<(), String> {
+    let file = File::create(path).map_err(|err| err.to_string())?;
+    let mut encoder = GifEncoder::new(BufWriter::new(file));
+    encoder.set_repeat(Repeat::Infinite).map_err(|err| err.to_string())?;
+
+    let frames = images.into_iter().map(|(image, duration)| Frame::from_parts(image, 0, 0, Delay::from_numer_denom_ms(duration, 1)));
+    encoder.encode_frames(frames).map_err(|err| err.to_string())
+}
+
+fn write_apng(path: &Path, images: Vec<(RgbaImage, u32)>, size: [u32; 2]) -> Result<(), String> {
+    let file = File::create(path).map_err(|err| err.to_string())?;
+    let mut encoder = png::Encoder::new(BufWriter::new(file), size[0], size[1]);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder.set_animated(images.len() as u32, 0).map_err(|err| err.to_string())?;
+
+    let mut writer = encoder.write_header().map_err(|err| err.to_string())?;
+    for (image, duration) in images {
+        writer.set_frame_delay(duration.min(u16::MAX as u32) as u16, 1000).map_err(|err| err.to_string())?;
+        writer.write_image_data(image.as_raw()).map_err(|err| err.to_string())?;
+    }
+    writer.finish().map_err(|err| err.to_string())
+}
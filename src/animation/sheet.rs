@@ -0,0 +1,271 @@
+use std::{fs, path::Path};
+
+use image::RgbaImage;
+use serde::{Deserialize, Deserializer, Serialize, de::{MapAccess, Visitor}};
+use serde_json::{from_str, to_string_pretty};
+
+use super::{DEFAULT_DURATION, Frame};
+use crate::transform::{Pixel, PixelArray};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SheetLayout {
+    /// Every frame gets the same sized cell, in rows as close to square as possible
+    Grid,
+    /// Frames are trimmed to their pixels and packed in shelves
+    Packed,
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize)]
+struct AtlasRect {
+    x: u32,
+    y: u32,
+    w: u32,
+    h: u32,
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize)]
+struct AtlasSize {
+    w: u32,
+    h: u32,
+}
+
+/// One frame of a sheet, in the shape both Aseprite and TexturePacker use
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AtlasFrame {
+    #[serde(default)]
+    filename: String,
+    frame: AtlasRect,
+    #[serde(default)]
+    rotated: bool,
+    #[serde(default)]
+    trimmed: bool,
+    sprite_source_size: AtlasRect,
+    source_size: AtlasSize,
+    #[serde(default = "default_duration")]
+    duration: u32,
+}
+
+fn default_duration() -> u32 {
+    DEFAULT_DURATION
+}
+
+#[derive(Serialize, Deserialize)]
+struct AtlasMeta {
+    app: String,
+    version: String,
+    image: String,
+    format: String,
+    size: AtlasSize,
+    scale: String,
+}
+
+/// Aseprite can write frames as an array or as a map from file name to frame
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum AtlasFrames {
+    Array(Vec<AtlasFrame>),
+    Hash(#[serde(deserialize_with = "in_file_order")] Vec<AtlasFrame>),
+}
+
+/// The frames of a map in the order they're written, since sorting by name would put `sprite 10` before `sprite 2`
+fn in_file_order<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<AtlasFrame>, D::Error> {
+    struct FramesVisitor;
+
+    impl<'de> Visitor<'de> for FramesVisitor {
+        type Value = Vec<AtlasFrame>;
+
+        fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            f.write_str("a map from file name to frame")
+        }
+
+        fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+            let mut frames = Vec::new();
+            while let Some((_, frame)) = map.next_entry::<String, AtlasFrame>()? {
+                frames.push(frame);
+            }
+            Ok(frames)
+        }
+    }
+
+    deserializer.deserialize_map(FramesVisitor)
+}
+
+#[derive(Serialize)]
+struct Atlas {
+    frames: Vec<AtlasFrame>,
+    meta: AtlasMeta,
+}
+
+#[derive(Deserialize)]
+struct AtlasIn {
+    frames: AtlasFrames,
+}
+
+/// The smallest area holding every visible pixel of `image`, or `None` if it's empty
+fn trim(image: &RgbaImage) -> Option<AtlasRect> {
+    let mut min = [u32::MAX; 2];
+    let mut max = [0; 2];
+    for (x, y, pixel) in image.enumerate_pixels() {
+        if pixel.0[3] != 0 {
+            min = [min[0].min(x), min[1].min(y)];
+            max = [max[0].max(x), max[1].max(y)];
+        }
+    }
+
+    (min[0] <= max[0]).then(|| AtlasRect { x: min[0], y: min[1], w: max[0] - min[0] + 1, h: max[1] - min[1] + 1 })
+}
+
+/// Where each of `sizes` goes in the sheet, filling shelves left to right, and the size of the sheet
+fn shelf_pack(sizes: &[AtlasSize]) -> (Vec<[u32; 2]>, AtlasSize) {
+    let area = sizes.iter().map(|size| size.w as u64 * size.h as u64).sum::<u64>();
+    let widest = sizes.iter().map(|size| size.w).max().unwrap_or(1);
+    let width = widest.max((area as f64).sqrt().ceil() as u32);
+
+    // Tallest first keeps the shelves from wasting space
+    let mut order = (0..sizes.len()).collect::<Vec<_>>();
+    order.sort_by_key(|&index| std::cmp::Reverse(sizes[index].h));
+
+    let mut positions = vec![[0, 0]; sizes.len()];
+    let (mut x, mut y, mut shelf, mut used) = (0, 0, 0, 0);
+    for index in order {
+        let size = sizes[index];
+        if x + size.w > width {
+            x = 0;
+            y += shelf;
+            shelf = 0;
+        }
+        positions[index] = [x, y];
+        x += size.w;
+        used = used.max(x);
+        shelf = shelf.max(size.h);
+    }
+
+    (positions, AtlasSize { w: used.max(1), h: (y + shelf).max(1) })
+}
+
+/// Packs `images` into one sheet, each shown for its duration in milliseconds, and writes it to `path` with a JSON atlas beside it
+pub fn write(path: &Path, images: Vec<(RgbaImage, u32)>, layout: SheetLayout) -> Result<(), String> {
+    let source_size = images.first().map(|(image, _)| AtlasSize { w: image.width(), h: image.height() }).unwrap_or(AtlasSize { w: 1, h: 1 });
+    let full = AtlasRect { x: 0, y: 0, w: source_size.w, h: source_size.h };
+
+    let sources = images.iter().map(|(image, _)| match layout {
+        SheetLayout::Grid => full,
+        SheetLayout::Packed => trim(image).unwrap_or(AtlasRect { x: 0, y: 0, w: 1, h: 1 }),
+    }).collect::<Vec<_>>();
+
+    let (positions, size) = match layout {
+        SheetLayout::Grid => {
+            let columns = (images.len() as f64).sqrt().ceil().max(1.0) as u32;
+            let rows = (images.len() as u32).div_ceil(columns).max(1);
+            let positions = (0..images.len() as u32).map(|index| [index % columns * source_size.w, index / columns * source_size.h]).collect();
+            (positions, AtlasSize { w: columns * source_size.w, h: rows * source_size.h })
+        },
+        SheetLayout::Packed => shelf_pack(&sources.iter().map(|rect| AtlasSize { w: rect.w, h: rect.h }).collect::<Vec<_>>()),
+    };
+
+    let name = path.file_stem().map(|d| d.to_string_lossy().to_string()).unwrap_or_default();
+    let mut sheet = RgbaImage::new(size.w, size.h);
+    let mut frames = Vec::with_capacity(images.len());
+    for (index, ((image, duration), (source, pos))) in images.iter().zip(sources.into_iter().zip(positions)).enumerate() {
+        let cropped = image::imageops::crop_imm(image, source.x, source.y, source.w, source.h).to_image();
+        image::imageops::replace(&mut sheet, &cropped, pos[0] as i64, pos[1] as i64);
+
+        frames.push(AtlasFrame {
+            filename: format!("{name} {index}.png"),
+            frame: AtlasRect { x: pos[0], y: pos[1], w: source.w, h: source.h },
+            rotated: false,
+            trimmed: layout == SheetLayout::Packed,
+            sprite_source_size: source,
+            source_size,
+            duration: *duration,
+        });
+    }
+
+    let atlas = Atlas {
+        frames,
+        meta: AtlasMeta {
+            app: env!("CARGO_PKG_NAME").to_string(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            image: path.file_name().map(|d| d.to_string_lossy().to_string()).unwrap_or_default(),
+            format: "RGBA8888".to_string(),
+            size,
+            scale: "1".to_string(),
+        },
+    };
+
+    sheet.save_with_format(path, image::ImageFormat::Png).map_err(|err| err.to_string())?;
+    fs::write(path.with_extension("json"), to_string_pretty(&atlas).unwrap()).map_err(|err| err.to_string())
+}
+
+/// Turns the visible pixels of `image` into a frame, with its top left at `offset`
+fn to_frame(image: &RgbaImage, offset: [u32; 2], duration: u32) -> Frame {
+    let mut pixels = PixelArray::default();
+    for (x, y, pixel) in image.enumerate_pixels() {
+        let [r, g, b, a] = pixel.0;
+        if a != 0 {
+            pixels.insert(Pixel {
+                pos: [(x + offset[0]) as i16, (y + offset[1]) as i16],
+                col: [r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0, a as f32 / 255.0],
+            });
+        }
+    }
+    Frame { pixels, duration }
+}
+
+pub fn load(path: &Path) -> Result<RgbaImage, String> {
+    let img = image::open(path).map_err(|err| format!("Error loading image `{}`:\n\n{err}", path.display()))?;
+    let img = img.to_rgba8();
+    if img.width() > i16::MAX as u32 || img.height() > i16::MAX as u32 {
+        return Err(format!("`{}` is too large to import.", path.display()));
+    }
+    Ok(img)
+}
+
+/// The atlas next to `path` if there is one, otherwise `None` so the sheet can be sliced by cell size
+pub fn slice_by_atlas(path: &Path, sheet: &RgbaImage) -> Option<Result<Vec<Frame>, String>> {
+    let atlas_path = path.with_extension("json");
+    let json = fs::read_to_string(&atlas_path).ok()?;
+    let error = |err: String| format!("Error parsing `{}`:\n\n{err}", atlas_path.display());
+
+    let frames = match from_str::<AtlasIn>(&json) {
+        Ok(atlas) => match atlas.frames {
+            AtlasFrames::Array(frames) | AtlasFrames::Hash(frames) => frames,
+        },
+        Err(err) => return Some(Err(error(err.to_string())))
+    };
+
+    let mut result = Vec::with_capacity(frames.len());
+    for frame in frames {
+        let rect = frame.frame;
+        if frame.rotated {
+            return Some(Err(error(format!("Frame `{}` is rotated, which isn't supported.", frame.filename))));
+        }
+        if rect.x.checked_add(rect.w).is_none_or(|r| r > sheet.width()) || rect.y.checked_add(rect.h).is_none_or(|b| b > sheet.height()) {
+            return Some(Err(error(format!("Frame `{}` goes outside the sheet.", frame.filename))));
+        }
+
+        let cropped = image::imageops::crop_imm(sheet, rect.x, rect.y, rect.w, rect.h).to_image();
+        let offset = [frame.sprite_source_size.x, frame.sprite_source_size.y];
+        if offset[0].saturating_add(rect.w) > i16::MAX as u32 || offset[1].saturating_add(rect.h) > i16::MAX as u32 {
+            return Some(Err(error(format!("Frame `{}` is offset too far to import.", frame.filename))));
+        }
+        result.push(to_frame(&cropped, offset, frame.duration));
+    }
+    Some(Ok(result))
+}
+
+/// Cuts `sheet` into `cell` sized frames, row by row, leaving out empty cells
+pub fn slice_by_cell(sheet: &RgbaImage, cell: [u32; 2]) -> Vec<Frame> {
+    let [w, h] = cell.map(|d| d.max(1));
+    let mut result = Vec::new();
+    for row in 0..sheet.height() / h {
+        for column in 0..sheet.width() / w {
+            let cropped = image::imageops::crop_imm(sheet, column * w, row * h, w, h).to_image();
+            if trim(&cropped).is_some() {
+                result.push(to_frame(&cropped, [0, 0], DEFAULT_DURATION));
+            }
+        }
+    }
+    result
+}
@@ -1,16 +1,23 @@
 use std::{fmt::{Debug, Display}, str::FromStr};
 
-use macroquad::{color::Color, math::{Vec3, Vec4}};
+use macroquad::{color::Color, math::{Vec2, Vec3, Vec4}};
 
 use oklab::{Oklab, Rgb, oklab_to_srgb_f32, srgb_f32_to_oklab};
 use serde::{Deserialize, Serialize};
 
 use crate::helpers::arr_to_macroquad;
 
+mod filter;
+pub use filter::Filter;
+
 pub enum Col {
     Rgba(Rgba),
     Hsva(Hsva),
     OkLab(OkLab),
+    Hsla(Hsla),
+    OkLch(OkLch),
+    CieLab(CieLab),
+    LinearRgba(LinearRgba),
 }
 
 #[derive(Debug, PartialEq, Hash, Eq, Clone, Copy, Deserialize, Serialize)]
@@ -18,6 +25,10 @@ pub enum ColSelection {
     Rgba,
     Hsva,
     OkLab,
+    Hsla,
+    OkLch,
+    CieLab,
+    LinearRgba,
 }
 
 impl Display for ColSelection {
@@ -26,6 +37,10 @@ impl Display for ColSelection {
             Self::Rgba => write!(f, "RGB"),
             Self::Hsva => write!(f, "HSV"),
             Self::OkLab => write!(f, "Ok Lab"),
+            Self::Hsla => write!(f, "HSL"),
+            Self::OkLch => write!(f, "Ok LCh"),
+            Self::CieLab => write!(f, "CIELAB"),
+            Self::LinearRgba => write!(f, "Linear RGB"),
         }
     }
 }
@@ -37,6 +52,10 @@ impl FromStr for ColSelection {
             "RGB" => Self::Rgba,
             "HSV" => Self::Hsva,
             "Ok Lab" => Self::OkLab,
+            "HSL" => Self::Hsla,
+            "Ok LCh" => Self::OkLch,
+            "CIELAB" => Self::CieLab,
+            "Linear RGB" => Self::LinearRgba,
             _ => return Err(())
         };
         Ok(result)
@@ -49,6 +68,10 @@ impl Col {
             Self::Rgba(col) => Box::new(*col) as Box<dyn ColType>,
             Self::Hsva(col) => Box::new(*col) as Box<dyn ColType>,
             Self::OkLab(col) => Box::new(*col) as Box<dyn ColType>,
+            Self::Hsla(col) => Box::new(*col) as Box<dyn ColType>,
+            Self::OkLch(col) => Box::new(*col) as Box<dyn ColType>,
+            Self::CieLab(col) => Box::new(*col) as Box<dyn ColType>,
+            Self::LinearRgba(col) => Box::new(*col) as Box<dyn ColType>,
         }
     }
 
@@ -77,6 +100,10 @@ impl Col {
             Self::Rgba(_) => ColSelection::Rgba,
             Self::Hsva(_) => ColSelection::Hsva,
             Self::OkLab(_) => ColSelection::OkLab,
+            Self::Hsla(_) => ColSelection::Hsla,
+            Self::OkLch(_) => ColSelection::OkLch,
+            Self::CieLab(_) => ColSelection::CieLab,
+            Self::LinearRgba(_) => ColSelection::LinearRgba,
         }
     }
 
@@ -84,6 +111,10 @@ impl Col {
         let rgb = self.to_rgba_u8();
         ColSelection::format_rgba_u8(rgb)
     }
+
+    pub fn describe(&self) -> String {
+        self.as_dyn().describe()
+    }
 }
 
 impl ColSelection {
@@ -95,11 +126,25 @@ impl ColSelection {
         Self::format_rgba_u8(arr.map(|d| (d * 255.0) as u8))
     }
 
+    /// Like `format_rgba`, but adds the alpha byte if the colour isn't opaque
+    pub fn format_rgba_alpha(arr: [f32; 4]) -> String {
+        let [r, g, b, a] = arr.map(|d| (d * 255.0) as u8);
+        if a == u8::MAX {
+            Self::format_rgba_u8([r, g, b, a])
+        } else {
+            format!("#{r:02X}{g:02X}{b:02X}{a:02X}")
+        }
+    }
+
     pub fn col_from_rgba(&self, r: f32, g: f32, b: f32, a: f32) -> Col {
         match self {
             Self::Rgba => Col::Rgba(Rgba::from_rgba(r, g, b, a)),
             Self::Hsva => Col::Hsva(Hsva::from_rgba(r, g, b, a)),
             Self::OkLab => Col::OkLab(OkLab::from_rgba(r, g, b, a)),
+            Self::Hsla => Col::Hsla(Hsla::from_rgba(r, g, b, a)),
+            Self::OkLch => Col::OkLch(OkLch::from_rgba(r, g, b, a)),
+            Self::CieLab => Col::CieLab(CieLab::from_rgba(r, g, b, a)),
+            Self::LinearRgba => Col::LinearRgba(LinearRgba::from_rgba(r, g, b, a)),
         }
     }
     
@@ -108,6 +153,10 @@ impl ColSelection {
             Self::Rgba => Col::Rgba(Rgba::from_wheel(circular, radial, scalar)),
             Self::Hsva => Col::Hsva(Hsva::from_wheel(circular, radial, scalar)),
             Self::OkLab => Col::OkLab(OkLab::from_wheel(circular, radial, scalar)),
+            Self::Hsla => Col::Hsla(Hsla::from_wheel(circular, radial, scalar)),
+            Self::OkLch => Col::OkLch(OkLch::from_wheel(circular, radial, scalar)),
+            Self::CieLab => Col::CieLab(CieLab::from_wheel(circular, radial, scalar)),
+            Self::LinearRgba => Col::LinearRgba(LinearRgba::from_wheel(circular, radial, scalar)),
         }
     }
     
@@ -128,9 +177,14 @@ impl ColSelection {
         self.col_from_rgba_hex((hex << 8) + 0xFF)
     }
 
-    /// Panics if string isnt only 0-9, a-z, A-Z
+    /// Takes `RRGGBB` or `RRGGBBAA`. Panics if string isnt only 0-9, a-z, A-Z
     pub fn col_from_hex_string(&self, hex: &str) -> Col {
-        self.col_from_rgb_hex(u32::from_str_radix(hex, 16).unwrap())
+        let value = u32::from_str_radix(hex, 16).unwrap();
+        if hex.len() == 8 {
+            self.col_from_rgba_hex(value)
+        } else {
+            self.col_from_rgb_hex(value)
+        }
     }
 
     pub fn default_cirular(&self) -> f32 {
@@ -138,6 +192,10 @@ impl ColSelection {
             Self::Rgba => Rgba::default_circular(),
             Self::Hsva => Hsva::default_circular(),
             Self::OkLab => OkLab::default_circular(),
+            Self::Hsla => Hsla::default_circular(),
+            Self::OkLch => OkLch::default_circular(),
+            Self::CieLab => CieLab::default_circular(),
+            Self::LinearRgba => LinearRgba::default_circular(),
         }
     }
     
@@ -146,6 +204,10 @@ impl ColSelection {
             Self::Rgba => Rgba::default_radial(),
             Self::Hsva => Hsva::default_radial(),
             Self::OkLab => OkLab::default_radial(),
+            Self::Hsla => Hsla::default_radial(),
+            Self::OkLch => OkLch::default_radial(),
+            Self::CieLab => CieLab::default_radial(),
+            Self::LinearRgba => LinearRgba::default_radial(),
         }
     }
 
@@ -154,14 +216,40 @@ impl ColSelection {
             Self::Rgba => Rgba::default_scalar(),
             Self::Hsva => Hsva::default_scalar(),
             Self::OkLab => OkLab::default_scalar(),
+            Self::Hsla => Hsla::default_scalar(),
+            Self::OkLch => OkLch::default_scalar(),
+            Self::CieLab => CieLab::default_scalar(),
+            Self::LinearRgba => LinearRgba::default_scalar(),
         }
     }
 
+    /// Interpolates between two rgba colours in this colour space, `frac` going from 0 (`from`) to 1 (`to`)
+    pub fn gradient(&self, from: [f32; 4], to: [f32; 4], frac: f32) -> [f32; 4] {
+        match self {
+            Self::Rgba => Rgba::from_rgba_arr(from).gradient(to, frac).to_rgba(),
+            Self::Hsva => Hsva::from_rgba_arr(from).gradient(to, frac).to_rgba(),
+            Self::OkLab => OkLab::from_rgba_arr(from).gradient(to, frac).to_rgba(),
+            Self::Hsla => Hsla::from_rgba_arr(from).gradient(to, frac).to_rgba(),
+            Self::OkLch => OkLch::from_rgba_arr(from).gradient(to, frac).to_rgba(),
+            Self::CieLab => CieLab::from_rgba_arr(from).gradient(to, frac).to_rgba(),
+            Self::LinearRgba => LinearRgba::from_rgba_arr(from).gradient(to, frac).to_rgba(),
+        }
+    }
+
+    /// Whether the circular wheel coordinate is a hue, and so wraps around from 1 back to 0
+    pub fn is_polar(&self) -> bool {
+        matches!(self, Self::Hsva | Self::Hsla | Self::OkLch)
+    }
+
     pub fn toggle(&self) -> Self {
         match self {
             ColSelection::Rgba => ColSelection::Hsva,
             ColSelection::Hsva => ColSelection::OkLab,
-            ColSelection::OkLab => ColSelection::Rgba,
+            ColSelection::OkLab => ColSelection::Hsla,
+            ColSelection::Hsla => ColSelection::OkLch,
+            ColSelection::OkLch => ColSelection::CieLab,
+            ColSelection::CieLab => ColSelection::LinearRgba,
+            ColSelection::LinearRgba => ColSelection::Rgba,
         }
     }
 }
@@ -177,6 +265,8 @@ pub trait ColType {
     fn from_wheel(circular: f32, radial: f32, scalar: f32) -> Self where Self: Sized;
     fn to_wheel(&self) -> (f32, f32, f32);
     fn gradient(&self, other: [f32; 4], frac: f32) -> Self where Self: Sized;
+    /// Human readable channel values, e.g. `HSV 120°, 50%, 100%`
+    fn describe(&self) -> String;
 
     fn from_rgba_arr(arr: [f32; 4]) -> Self where Self: std::marker::Sized {
         Self::from_rgba(arr[0], arr[1], arr[2], arr[3])
@@ -245,6 +335,10 @@ impl ColType for Rgba {
 
         Self::from_rgba_arr(vec.lerp(other, frac).to_array())
     }
+
+    fn describe(&self) -> String {
+        format!("RGB {}, {}, {}", (self.r * 255.0).round(), (self.g * 255.0).round(), (self.b * 255.0).round())
+    }
 }
 
 #[derive(Clone, Copy, Default)]
@@ -362,11 +456,15 @@ impl ColType for Hsva {
         let result = Vec4::new(x1, y1, z1, w1).lerp(Vec4::new(x2, y2, z2, w2), frac);
 
         let v = result.z;
-        let h = f32::atan2(result.y, result.x) / std::f32::consts::TAU;
-        let s = result.y / v / f32::sin(h * std::f32::consts::TAU);
+        let h = (f32::atan2(result.y, result.x) / std::f32::consts::TAU).rem_euclid(1.0);
+        let s = if v > 0.0 { (Vec2::new(result.x, result.y).length() / v).min(1.0) } else { 0.0 };
         let a = result.w;
-        
-        Self { h, s, v, a } // todo!() CHECK this works
+
+        Self { h, s, v, a }
+    }
+
+    fn describe(&self) -> String {
+        format!("HSV {}°, {}%, {}%", (self.h * 360.0).round(), (self.s * 100.0).round(), (self.v * 100.0).round())
     }
 }
 
@@ -438,9 +536,21 @@ impl ColType for OkLab {
             )
         )
     }
+
+    fn describe(&self) -> String {
+        format!("OkLab {:.3}, {:.3}, {:.3}", self.oklab.l, self.oklab.a, self.oklab.b)
+    }
 }
 
 impl OkLab {
+    pub fn new(l: f32, a: f32, b: f32, alpha: f32) -> Self {
+        Self { oklab: Oklab { l, a, b }, a: alpha }
+    }
+
+    pub fn lab(&self) -> [f32; 3] {
+        [self.oklab.l, self.oklab.a, self.oklab.b]
+    }
+
     fn to_vec(self) -> Vec4 {
         Vec4::new(self.oklab.l, self.oklab.a, self.oklab.b, self.a)
     }
@@ -455,4 +565,521 @@ impl OkLab {
             a: vec.w
         }
     }
-}
\ No newline at end of file
+}
+
+/// Alpha weighted average of `cols` in `col_sel`, or `None` if there are none.
+///
+/// Rgb is averaged in linear light and hue round the circle. The alpha is only below 1 if a colour was see through.
+pub fn average_cols(cols: impl IntoIterator<Item = [f32; 4]>, col_sel: ColSelection) -> Option<[f32; 4]> {
+    // Gamma encoded values aren't proportional to light, so average rgb in linear light instead
+    let col_sel = match col_sel {
+        ColSelection::Rgba => ColSelection::LinearRgba,
+        col_sel => col_sel
+    };
+    let polar = col_sel.is_polar();
+
+    let mut sx = 0.0;
+    let mut sy = 0.0;
+    let mut sz = 0.0;
+    let mut sa = 0.0;
+    let mut hue_x = 0.0;
+    let mut hue_y = 0.0;
+    let mut count = 0;
+    let mut all_full = true;
+
+    for col in cols {
+        let fa = col[3];
+        if fa != 1.0 {
+            all_full = false;
+        }
+        count += 1;
+        sa += fa;
+
+        let (circular, radial, scalar) = col_sel.col_from_rgba_arr(col).to_wheel();
+
+        // Hue wraps around, so take the circular mean, weighted by how colourful each pixel is
+        if polar {
+            let angle = circular * std::f32::consts::TAU;
+            hue_x += angle.cos() * radial * fa;
+            hue_y += angle.sin() * radial * fa;
+        } else {
+            sx += circular * fa;
+        }
+        sy += radial * fa;
+        sz += scalar * fa;
+    }
+
+    if count == 0 {
+        return None;
+    } else if sa == 0.0 {
+        return Some([0.0; 4]);
+    }
+
+    // Weighting by alpha then dividing by total alpha averages the premultiplied colour
+    sx = if polar {
+        (f32::atan2(hue_y, hue_x) / std::f32::consts::TAU).rem_euclid(1.0)
+    } else {
+        sx / sa
+    };
+    sy /= sa;
+    sz /= sa;
+
+    let mut result = col_sel.col_from_wheel(sx, sy, sz).to_rgba();
+    result[3] = if all_full {1.0} else {sa / count as f32};
+    Some(result)
+}
+
+/// OkLab distance with the lightness and chroma differences scaled separately
+pub fn weighted_oklab_distance(col: [f32; 4], other: [f32; 4], lightness: f32, chroma: f32) -> f32 {
+    let col = srgb_f32_to_oklab(Rgb { r: col[0], g: col[1], b: col[2] });
+    let other = srgb_f32_to_oklab(Rgb { r: other[0], g: other[1], b: other[2] });
+
+    let dl = (col.l - other.l) * lightness;
+    let dc = Vec2::new(col.a - other.a, col.b - other.b).length() * chroma;
+
+    Vec2::new(dl, dc).length() * OKLABSCALE
+}
+
+pub fn srgb_to_linear(c: f32) -> f32 {
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+pub fn linear_to_srgb(c: f32) -> f32 {
+    if c <= 0.0031308 {
+        c * 12.92
+    } else {
+        1.055 * c.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+/// Interpolates between two hues (as fractions of a turn) the short way round
+fn lerp_hue(from: f32, to: f32, frac: f32) -> f32 {
+    let diff = (to - from + 0.5).rem_euclid(1.0) - 0.5;
+    (from + diff * frac).rem_euclid(1.0)
+}
+
+#[derive(Clone, Copy, Default)]
+pub struct Hsla {
+    h: f32,
+    s: f32,
+    l: f32,
+    a: f32
+}
+
+impl Hsla {
+    fn to_cone(self) -> Vec4 {
+        let chroma = self.s * (1.0 - (2.0 * self.l - 1.0).abs());
+        Vec4::new(
+            chroma * f32::cos(self.h * std::f32::consts::TAU),
+            chroma * f32::sin(self.h * std::f32::consts::TAU),
+            self.l,
+            self.a
+        )
+    }
+}
+
+impl ColType for Hsla {
+    fn default_circular() -> f32 {0.0}
+    fn default_radial() -> f32 {1.0}
+    fn default_scalar() -> f32 {0.5}
+
+    fn to_rgba(&self) -> [f32; 4] {
+        let s = self.s.clamp(0.0, 1.0);
+        let l = self.l.clamp(0.0, 1.0);
+
+        // Same hue, just with hsv's value and saturation
+        let v = l + s * l.min(1.0 - l);
+        let sv = if v > 0.0 { 2.0 * (1.0 - l / v) } else { 0.0 };
+
+        Hsva { h: self.h, s: sv, v, a: self.a }.to_rgba()
+    }
+
+    fn from_rgba(r: f32, g: f32, b: f32, a: f32) -> Self {
+        let hsva = Hsva::from_rgba(r, g, b, a);
+        let l = hsva.v * (1.0 - hsva.s / 2.0);
+        let s = if l > 0.0 && l < 1.0 { (hsva.v - l) / l.min(1.0 - l) } else { 0.0 };
+
+        Self { h: hsva.h, s, l, a: hsva.a }
+    }
+
+    fn distance(&self, other: [f32; 4]) -> f32 {
+        let other = Self::from_rgba_arr(other);
+
+        self.to_cone().truncate().distance(other.to_cone().truncate()) * 0.5
+    }
+
+    fn from_wheel(circular: f32, radial: f32, scalar: f32) -> Self {
+        Self {
+            h: circular,
+            s: radial,
+            l: scalar,
+            a: 1.0
+        }
+    }
+
+    fn to_wheel(&self) -> (f32, f32, f32) {
+        (self.h, self.s, self.l)
+    }
+
+    fn gradient(&self, other: [f32; 4], frac: f32) -> Self {
+        let result = self.to_cone().lerp(Self::from_rgba_arr(other).to_cone(), frac);
+
+        let l = result.z;
+        let h = (f32::atan2(result.y, result.x) / std::f32::consts::TAU).rem_euclid(1.0);
+        let max_chroma = 1.0 - (2.0 * l - 1.0).abs();
+        let s = if max_chroma > 0.0 { (Vec2::new(result.x, result.y).length() / max_chroma).min(1.0) } else { 0.0 };
+
+        Self { h, s, l, a: result.w }
+    }
+
+    fn describe(&self) -> String {
+        format!("HSL {}°, {}%, {}%", (self.h * 360.0).round(), (self.s * 100.0).round(), (self.l * 100.0).round())
+    }
+}
+
+// Roughly the highest chroma any srgb colour reaches
+const OKLCH_MAX_CHROMA: f32 = 0.33;
+
+/// Polar OkLab, `h` is a fraction of a turn
+#[derive(Clone, Copy)]
+pub struct OkLch {
+    l: f32,
+    c: f32,
+    h: f32,
+    a: f32
+}
+
+impl OkLch {
+    fn from_oklab(oklab: OkLab) -> Self {
+        let c = Vec2::new(oklab.oklab.a, oklab.oklab.b).length();
+        let h = (f32::atan2(oklab.oklab.b, oklab.oklab.a) / std::f32::consts::TAU).rem_euclid(1.0);
+
+        Self { l: oklab.oklab.l, c, h, a: oklab.a }
+    }
+
+    fn to_oklab(self) -> OkLab {
+        let angle = self.h * std::f32::consts::TAU;
+        OkLab {
+            oklab: Oklab { l: self.l, a: self.c * angle.cos(), b: self.c * angle.sin() },
+            a: self.a
+        }
+    }
+}
+
+impl ColType for OkLch {
+    fn default_circular() -> f32 {0.0}
+    fn default_radial() -> f32 {0.5}
+    fn default_scalar() -> f32 {0.7}
+
+    fn to_rgba(&self) -> [f32; 4] {
+        // Most of the wheel is outside srgb, so clamp rather than hand back nonsense
+        self.to_oklab().to_rgba().map(|d| d.clamp(0.0, 1.0))
+    }
+
+    fn from_rgba(r: f32, g: f32, b: f32, a: f32) -> Self {
+        Self::from_oklab(OkLab::from_rgba(r, g, b, a))
+    }
+
+    fn distance(&self, other: [f32; 4]) -> f32 {
+        self.to_oklab().distance(other)
+    }
+
+    fn from_wheel(circular: f32, radial: f32, scalar: f32) -> Self {
+        Self {
+            l: scalar,
+            c: radial * OKLCH_MAX_CHROMA,
+            h: circular,
+            a: 1.0
+        }
+    }
+
+    fn to_wheel(&self) -> (f32, f32, f32) {
+        (self.h, (self.c / OKLCH_MAX_CHROMA).min(1.0), self.l)
+    }
+
+    fn gradient(&self, other: [f32; 4], frac: f32) -> Self {
+        let other = Self::from_rgba_arr(other);
+
+        // A grey has no real hue, so take the other end's
+        let (from_h, to_h) = match (self.c < 1e-4, other.c < 1e-4) {
+            (true, false) => (other.h, other.h),
+            (false, true) => (self.h, self.h),
+            _ => (self.h, other.h),
+        };
+
+        Self {
+            l: self.l + (other.l - self.l) * frac,
+            c: self.c + (other.c - self.c) * frac,
+            h: lerp_hue(from_h, to_h, frac),
+            a: self.a + (other.a - self.a) * frac
+        }
+    }
+
+    fn describe(&self) -> String {
+        format!("OkLCh {:.3}, {:.3}, {}°", self.l, self.c, (self.h * 360.0).round())
+    }
+}
+
+// D65 white point
+const WHITE_X: f32 = 0.95047;
+const WHITE_Y: f32 = 1.0;
+const WHITE_Z: f32 = 1.08883;
+
+// Range of a and b over srgb
+const CIELAB_MIN_A: f32 = -86.2;
+const CIELAB_MAX_A: f32 = 98.3;
+const CIELAB_MIN_B: f32 = -107.9;
+const CIELAB_MAX_B: f32 = 94.5;
+
+/// CIE L*a*b* under D65, `l` from 0 to 100
+#[derive(Clone, Copy)]
+pub struct CieLab {
+    l: f32,
+    a: f32,
+    b: f32,
+    alpha: f32
+}
+
+impl CieLab {
+    pub fn new(l: f32, a: f32, b: f32, alpha: f32) -> Self {
+        Self { l, a, b, alpha }
+    }
+
+    fn to_vec(self) -> Vec4 {
+        Vec4::new(self.l, self.a, self.b, self.alpha)
+    }
+
+    /// CIEDE2000 colour difference
+    fn delta_e(&self, other: &Self) -> f32 {
+        let (l1, a1, b1) = (self.l, self.a, self.b);
+        let (l2, a2, b2) = (other.l, other.a, other.b);
+        let pow7 = |x: f32| x.powi(7);
+        let deg = |x: f32| x.to_degrees().rem_euclid(360.0);
+
+        let c_bar = (Vec2::new(a1, b1).length() + Vec2::new(a2, b2).length()) / 2.0;
+        let g = 0.5 * (1.0 - (pow7(c_bar) / (pow7(c_bar) + pow7(25.0))).sqrt());
+        let (a1, a2) = ((1.0 + g) * a1, (1.0 + g) * a2);
+
+        let (c1, c2) = (Vec2::new(a1, b1).length(), Vec2::new(a2, b2).length());
+        let h1 = if c1 == 0.0 { 0.0 } else { deg(b1.atan2(a1)) };
+        let h2 = if c2 == 0.0 { 0.0 } else { deg(b2.atan2(a2)) };
+
+        let dl = l2 - l1;
+        let dc = c2 - c1;
+        let dh = if c1 * c2 == 0.0 {
+            0.0
+        } else if (h2 - h1).abs() <= 180.0 {
+            h2 - h1
+        } else if h2 > h1 {
+            h2 - h1 - 360.0
+        } else {
+            h2 - h1 + 360.0
+        };
+        let dh = 2.0 * (c1 * c2).sqrt() * (dh / 2.0).to_radians().sin();
+
+        let l_bar = (l1 + l2) / 2.0;
+        let c_bar = (c1 + c2) / 2.0;
+        let h_bar = if c1 * c2 == 0.0 {
+            h1 + h2
+        } else if (h1 - h2).abs() <= 180.0 {
+            (h1 + h2) / 2.0
+        } else if h1 + h2 < 360.0 {
+            (h1 + h2 + 360.0) / 2.0
+        } else {
+            (h1 + h2 - 360.0) / 2.0
+        };
+
+        let t = 1.0
+            - 0.17 * (h_bar - 30.0).to_radians().cos()
+            + 0.24 * (2.0 * h_bar).to_radians().cos()
+            + 0.32 * (3.0 * h_bar + 6.0).to_radians().cos()
+            - 0.20 * (4.0 * h_bar - 63.0).to_radians().cos();
+        let d_theta = 30.0 * (-((h_bar - 275.0) / 25.0).powi(2)).exp();
+        let r_c = 2.0 * (pow7(c_bar) / (pow7(c_bar) + pow7(25.0))).sqrt();
+        let s_l = 1.0 + 0.015 * (l_bar - 50.0).powi(2) / (20.0 + (l_bar - 50.0).powi(2)).sqrt();
+        let s_c = 1.0 + 0.045 * c_bar;
+        let s_h = 1.0 + 0.015 * c_bar * t;
+        let r_t = -(2.0 * d_theta).to_radians().sin() * r_c;
+
+        ((dl / s_l).powi(2) + (dc / s_c).powi(2) + (dh / s_h).powi(2) + r_t * (dc / s_c) * (dh / s_h)).sqrt()
+    }
+}
+
+impl ColType for CieLab {
+    fn default_circular() -> f32 {0.0}
+    fn default_radial() -> f32 {0.0}
+    fn default_scalar() -> f32 {0.0}
+
+    fn to_rgba(&self) -> [f32; 4] {
+        let finv = |t: f32| if t > 6.0 / 29.0 { t * t * t } else { 3.0 * (6.0f32 / 29.0).powi(2) * (t - 4.0 / 29.0) };
+        let fy = (self.l + 16.0) / 116.0;
+        let x = WHITE_X * finv(fy + self.a / 500.0);
+        let y = WHITE_Y * finv(fy);
+        let z = WHITE_Z * finv(fy - self.b / 200.0);
+
+        let r = 3.2404542 * x - 1.5371385 * y - 0.4985314 * z;
+        let g = -0.969266 * x + 1.8760108 * y + 0.0415560 * z;
+        let b = 0.0556434 * x - 0.2040259 * y + 1.0572252 * z;
+
+        [
+            linear_to_srgb(r.clamp(0.0, 1.0)),
+            linear_to_srgb(g.clamp(0.0, 1.0)),
+            linear_to_srgb(b.clamp(0.0, 1.0)),
+            self.alpha
+        ]
+    }
+
+    fn from_rgba(r: f32, g: f32, b: f32, a: f32) -> Self {
+        let (r, g, b) = (srgb_to_linear(r), srgb_to_linear(g), srgb_to_linear(b));
+        let x = 0.4124564 * r + 0.3575761 * g + 0.1804375 * b;
+        let y = 0.2126729 * r + 0.7151522 * g + 0.0721750 * b;
+        let z = 0.0193339 * r + 0.119192 * g + 0.9503041 * b;
+
+        let f = |t: f32| if t > (6.0f32 / 29.0).powi(3) { t.cbrt() } else { t / (3.0 * (6.0f32 / 29.0).powi(2)) + 4.0 / 29.0 };
+        let (fx, fy, fz) = (f(x / WHITE_X), f(y / WHITE_Y), f(z / WHITE_Z));
+
+        Self {
+            l: 116.0 * fy - 16.0,
+            a: 500.0 * (fx - fy),
+            b: 200.0 * (fy - fz),
+            alpha: a
+        }
+    }
+
+    fn distance(&self, other: [f32; 4]) -> f32 {
+        // Black to white is 100
+        self.delta_e(&Self::from_rgba_arr(other)) / 100.0
+    }
+
+    fn from_wheel(circular: f32, radial: f32, scalar: f32) -> Self {
+        Self {
+            l: scalar * 100.0,
+            a: circular * (CIELAB_MAX_A - CIELAB_MIN_A) + CIELAB_MIN_A,
+            b: radial * (CIELAB_MAX_B - CIELAB_MIN_B) + CIELAB_MIN_B,
+            alpha: 1.0
+        }
+    }
+
+    fn to_wheel(&self) -> (f32, f32, f32) {
+        (
+            (self.a - CIELAB_MIN_A) / (CIELAB_MAX_A - CIELAB_MIN_A),
+            (self.b - CIELAB_MIN_B) / (CIELAB_MAX_B - CIELAB_MIN_B),
+            self.l / 100.0
+        )
+    }
+
+    fn gradient(&self, other: [f32; 4], frac: f32) -> Self {
+        let vec = self.to_vec().lerp(Self::from_rgba_arr(other).to_vec(), frac);
+
+        Self::new(vec.x, vec.y, vec.z, vec.w)
+    }
+
+    fn describe(&self) -> String {
+        format!("CIELAB {:.1}, {:.1}, {:.1}", self.l, self.a, self.b)
+    }
+}
+
+/// Rgba without the srgb transfer curve, so channels are proportional to light
+#[derive(Clone, Copy, Default)]
+pub struct LinearRgba {
+    r: f32,
+    g: f32,
+    b: f32,
+    a: f32
+}
+
+impl LinearRgba {
+    fn to_vec(self) -> Vec4 {
+        Vec4::new(self.r, self.g, self.b, self.a)
+    }
+}
+
+impl ColType for LinearRgba {
+    fn default_circular() -> f32 {0.0}
+    fn default_radial() -> f32 {0.0}
+    fn default_scalar() -> f32 {0.0}
+
+    fn to_rgba(&self) -> [f32; 4] {
+        [linear_to_srgb(self.r), linear_to_srgb(self.g), linear_to_srgb(self.b), self.a]
+    }
+
+    fn from_rgba(r: f32, g: f32, b: f32, a: f32) -> Self {
+        Self { r: srgb_to_linear(r), g: srgb_to_linear(g), b: srgb_to_linear(b), a }
+    }
+
+    fn distance(&self, other: [f32; 4]) -> f32 {
+        self.to_vec().distance(Self::from_rgba_arr(other).to_vec()) * FRAC_1_SQRT_4
+    }
+
+    fn from_wheel(circular: f32, radial: f32, scalar: f32) -> Self {
+        Self {
+            r: circular,
+            g: radial,
+            b: scalar,
+            a: 1.0
+        }
+    }
+
+    fn to_wheel(&self) -> (f32, f32, f32) {
+        (self.r, self.g, self.b)
+    }
+
+    fn gradient(&self, other: [f32; 4], frac: f32) -> Self {
+        let vec = self.to_vec().lerp(Self::from_rgba_arr(other).to_vec(), frac);
+
+        Self { r: vec.x, g: vec.y, b: vec.z, a: vec.w }
+    }
+
+    fn describe(&self) -> String {
+        format!("Linear RGB {:.3}, {:.3}, {:.3}", self.r, self.g, self.b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(result: [f32; 4], expected: [f32; 4]) {
+        assert!(
+            result.iter().zip(expected).all(|(a, b)| (a - b).abs() < 1e-3),
+            "expected {expected:?}, got {result:?}"
+        );
+    }
+
+    #[test]
+    fn average_hue_wraps_round_red() {
+        // Hues of about 0.01 and 0.99, which average to 0.5 (cyan) if the wrap is ignored
+        let cols = [[1.0, 0.06, 0.0, 1.0], [1.0, 0.0, 0.06, 1.0]];
+
+        let result = average_cols(cols, ColSelection::Hsva).unwrap();
+        assert_close(result, [1.0, 0.0, 0.0, 1.0]);
+    }
+
+    #[test]
+    fn average_rgb_in_linear_light() {
+        let cols = [[0.0, 0.0, 0.0, 1.0], [1.0, 1.0, 1.0, 1.0]];
+
+        let result = average_cols(cols, ColSelection::Rgba).unwrap();
+        let half = linear_to_srgb(0.5);
+        assert!((half - 0.735).abs() < 1e-3);
+        assert_close(result, [half, half, half, 1.0]);
+    }
+
+    #[test]
+    fn average_weights_by_alpha() {
+        let cols = [[1.0, 0.0, 0.0, 1.0], [0.0, 0.0, 1.0, 0.5]];
+
+        let result = average_cols(cols, ColSelection::Rgba).unwrap();
+        assert_close(result, [linear_to_srgb(2.0 / 3.0), 0.0, linear_to_srgb(1.0 / 3.0), 0.75]);
+    }
+
+    #[test]
+    fn average_of_nothing() {
+        assert_eq!(average_cols([], ColSelection::Rgba), None);
+        assert_eq!(average_cols([[1.0, 1.0, 1.0, 0.0]], ColSelection::Rgba), Some([0.0; 4]));
+    }
+}
@@ -0,0 +1,69 @@
+use super::{ColType, OkLab, OkLch};
+
+/// An adjustment made to every colour in the drawing or a selection
+#[derive(Clone, Debug, PartialEq)]
+pub enum Filter {
+    /// Turns the OkLch hue by a fraction of a turn
+    HueShift(f32),
+    /// Multiplies the OkLch chroma
+    Saturation(f32),
+    /// Added to the OkLab lightness
+    Brightness(f32),
+    Invert,
+    /// Colours within `tolerance` OkLab distance of `from` become `to`
+    Replace { from: [f32; 4], to: [f32; 4], tolerance: f32 },
+    /// Swaps each colour for the nearest in OkLab of a palette, usually picked from the colours being filtered
+    Posterise(Vec<[f32; 4]>),
+    /// Keeps the OkLab lightness and drops the colour
+    Grayscale,
+}
+
+impl Filter {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::HueShift(_) => "Hue shift",
+            Self::Saturation(_) => "Saturation",
+            Self::Brightness(_) => "Brightness",
+            Self::Invert => "Invert",
+            Self::Replace { .. } => "Replace",
+            Self::Posterise(_) => "Posterise",
+            Self::Grayscale => "Grayscale",
+        }
+    }
+
+    /// Alpha is left alone, apart from replacing, which swaps the whole colour
+    pub fn apply(&self, col: [f32; 4]) -> [f32; 4] {
+        match self {
+            Self::HueShift(turns) => {
+                let mut lch = OkLch::from_rgba_arr(col);
+                lch.h = (lch.h + *turns).rem_euclid(1.0);
+                lch.to_rgba()
+            },
+            Self::Saturation(factor) => {
+                let mut lch = OkLch::from_rgba_arr(col);
+                lch.c *= factor.max(0.0);
+                lch.to_rgba()
+            },
+            Self::Brightness(amount) => {
+                let mut lch = OkLch::from_rgba_arr(col);
+                lch.l = (lch.l + amount).clamp(0.0, 1.0);
+                lch.to_rgba()
+            },
+            Self::Invert => [1.0 - col[0], 1.0 - col[1], 1.0 - col[2], col[3]],
+            Self::Replace { from, to, tolerance } => {
+                // Fully transparent pixels match whatever colour they were left with, so compare alpha too
+                let same = OkLab::from_rgba_arr(*from).distance(col) <= *tolerance && (from[3] - col[3]).abs() <= *tolerance;
+                if same { *to } else { col }
+            },
+            Self::Posterise(palette) => {
+                let lab = OkLab::from_rgba_arr(col);
+                let Some(&[r, g, b, _]) = palette.iter().min_by(|a, b| lab.distance(**a).total_cmp(&lab.distance(**b))) else { return col };
+                [r, g, b, col[3]]
+            },
+            Self::Grayscale => {
+                let [l, _, _] = OkLab::from_rgba_arr(col).lab();
+                OkLab::new(l, 0.0, 0.0, col[3]).to_rgba().map(|d| d.clamp(0.0, 1.0))
+            },
+        }
+    }
+}
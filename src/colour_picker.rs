@@ -59,19 +59,10 @@ impl Node for PickerNode {
     fn update(&mut self, ctx: &mut AppContextHandler, node: &NodeStore) {
         if let ShortcutInstruction::ChangePickerType(pickertype) = ctx.user_inputs.pressed_instruction {
             let picker = ctx.store.get_mut::<Picker>();
+            picker.transfer_col(pickertype);
             match pickertype {
-                ColSelection::Rgba => {
-                    picker.transfer_col(ColSelection::Rgba);
-                    picker.transfer_picker(PickerSelection::Linear);
-                },
-                ColSelection::Hsva => {
-                    picker.transfer_col(ColSelection::Hsva);
-                    picker.transfer_picker(PickerSelection::Circular);
-                },
-                ColSelection::OkLab => {
-                    picker.transfer_col(ColSelection::OkLab);
-                    picker.transfer_picker(PickerSelection::Linear);
-                }
+                ColSelection::Hsva | ColSelection::Hsla | ColSelection::OkLch => picker.transfer_picker(PickerSelection::Circular),
+                ColSelection::Rgba | ColSelection::OkLab | ColSelection::CieLab | ColSelection::LinearRgba => picker.transfer_picker(PickerSelection::Linear),
             }
         }
 
@@ -93,7 +84,7 @@ impl Node for PickerNode {
 
 impl New for PickerNode {
     fn new(handler: &mut GenHandler) -> Self {
-        handler.push_data(Picker::new_circular(50.0, 10.0, [10.0, 270.0], 16.0, ColSelection::Hsva));
+        handler.push_data(Picker::new_circular(46.0, 10.0, [10.0, 270.0], 16.0, ColSelection::Hsva));
         Self {
         }
     }
@@ -113,9 +104,40 @@ pub trait ColPicker {
     fn transfer_col(&mut self, coltype: ColSelection);
     fn transfer_picker(self, pickertype: PickerSelection) -> PickerEnum;
     fn get_col_rgba(&mut self) -> Option<[f32; 4]>;
+    fn get_coltype(&self) -> ColSelection;
 }
 
 
+// Gap between the scalar slider and the alpha slider
+const ALPHA_PADDING: f32 = 8.0;
+
+/// Draws an alpha slider fading `col` in over a checkerboard, with the top fully opaque
+fn draw_alpha_slider(x: f32, width: f32, height: f32, col: [f32; 4]) {
+    let check = width / 2.0;
+    for row in 0..(height / check).ceil() as u16 {
+        let y = row as f32 * check;
+        for column in 0..2 {
+            let shade = if (row + column) % 2 == 0 { LIGHTGRAY } else { WHITE };
+            draw_rectangle(x + column as f32 * check, y, check, check.min(height - y), shade);
+        }
+    }
+
+    for value in 0..height as u16 {
+        let alpha = 1.0 - value as f32 / height;
+        draw_rectangle(x, value as f32, width, 1.0, Color::new(col[0], col[1], col[2], alpha));
+    }
+}
+
+fn draw_alpha_marker(x: f32, y: f32, height: f32, alpha: f32) {
+    let y = y + height * (1.0 - alpha);
+    draw_triangle(
+        vec2(x, y),
+        vec2(x - ALPHA_PADDING / 2.0, y - ALPHA_PADDING / 3.0),
+        vec2(x - ALPHA_PADDING / 2.0, y + ALPHA_PADDING / 3.0),
+        Color::from_hex(0xFFFFFF)
+    );
+}
+
 pub struct RedrawGuard<'a> {
     surface: &'a mut SurfaceCache
 }
@@ -1,7 +1,7 @@
 use macroquad::prelude::*;
 
 use crate::{colour::Col, colour_picker::{SurfaceCache, linear::Linear}};
-use super::{ColSelection, ColPicker, PickerEnum, PickerSelection};
+use super::{ColSelection, ColPicker, PickerEnum, PickerSelection, ALPHA_PADDING, draw_alpha_slider, draw_alpha_marker};
 
 
 pub struct Circular {
@@ -11,6 +11,8 @@ pub struct Circular {
     pub padding: f32,
     pub selected: Option<Col>,
     pub coltype: ColSelection,
+    /// Kept apart from `selected`, since colours made from the wheel are always opaque
+    pub alpha: f32,
     cached_col: Option<Option<[f32; 4]>>,
     surface_cache: SurfaceCache
 }
@@ -28,6 +30,7 @@ impl Circular {
             padding,
             selected: selected.map(|d| coltype.col_from_rgba_arr(d)),
             coltype,
+            alpha: selected.map_or(1.0, |d| d[3]),
             cached_col: Some(selected),
             surface_cache: SurfaceCache::new(Rect::default())
         };
@@ -41,7 +44,7 @@ impl Circular {
 impl ColPicker for Circular {
     fn bounding_box(&self) -> Rect {
         let height = self.radius * 2.0;
-        let width = height + self.padding + self.width;
+        let width = height + self.padding + self.width + ALPHA_PADDING + self.width;
 
         Rect::new(self.offset[0], self.offset[1], width, height)
     }
@@ -53,8 +56,16 @@ impl ColPicker for Circular {
         let height = self.radius * 2.0;
         let radial = circle_mouse.length() / self.radius;
         let slider_rect = Rect::new(height + self.padding, 0.0, self.width, height);
+        let alpha_rect = Rect::new(slider_rect.right() + ALPHA_PADDING, 0.0, self.width, height);
 
-        if slider_rect.contains(first_mouse_down - offset) {
+        if alpha_rect.contains(first_mouse_down - offset) {
+            self.cached_col = None;
+            self.surface_cache.invalidate();
+            self.alpha = (1.0 - mouse.y / height).clamp(0.0, 1.0);
+            if self.selected.is_none() {
+                self.selected = Some(self.coltype.col_from_wheel(self.coltype.default_cirular(), self.coltype.default_radial(), self.coltype.default_scalar()));
+            }
+        } else if slider_rect.contains(first_mouse_down - offset) {
             self.cached_col = None;
             self.surface_cache.invalidate();
             let scalar = (1.0 - mouse.y / height).clamp(0.0, 1.0);
@@ -125,12 +136,16 @@ impl ColPicker for Circular {
                 draw_rectangle(x, y, self.width, 1.0, self.coltype.col_from_wheel(circular, radial, 1.0 - value / max_value).to_macroquad_col());
             }
 
+            draw_alpha_slider(x + self.width + ALPHA_PADDING, self.width, max_value, self.coltype.col_from_wheel(circular, radial, scalar).to_rgba());
+
             wheel_selected
         } else {
             self.selected.as_ref().map(|col| col.to_wheel())
         };
     
         if let Some((circular, radial, scalar)) = wheel_selected {
+            draw_alpha_marker(self.offset[0] + self.radius * 2.0 + self.padding + self.width + ALPHA_PADDING, self.offset[1], self.radius * 2.0, self.alpha);
+
             let height = self.radius * 2.0;
             let x = height + self.padding + self.offset[0];
             let y = height * (1.0 - scalar) + self.offset[1];
@@ -153,16 +168,27 @@ impl ColPicker for Circular {
         match self.cached_col {
             Some(cached_col) => cached_col,
             None => {
-                let result = self.selected.as_ref().map(|d| d.to_rgba());
+                let result = self.selected.as_ref().map(|d| {
+                    let mut col = d.to_rgba();
+                    col[3] = self.alpha;
+                    col
+                });
                 self.cached_col = Some(result);
                 result
             }
         }
     }
 
+    fn get_coltype(&self) -> ColSelection {
+        self.coltype
+    }
+
     fn set_col(&mut self, col: Option<[f32; 4]>) {
         self.cached_col = Some(col);
         self.selected = col.map(|d| self.coltype.col_from_rgba_arr(d));
+        if let Some(col) = col {
+            self.alpha = col[3];
+        }
         self.surface_cache.invalidate();
     }
 
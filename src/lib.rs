@@ -1,6 +1,9 @@
 pub mod transform;
 pub mod colour_picker;
 pub mod colour;
+pub mod palette;
+pub mod reference;
+pub mod animation;
 pub mod helpers;
 pub mod ui;
 pub mod node;
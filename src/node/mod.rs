@@ -7,7 +7,7 @@ use std::rc::{Rc, Weak};
 mod user_inputs;
 pub use user_inputs::{UserInputs, ShortcutInstruction, Shortcuts, shortcut_to_string, prettify_camel_case};
 mod save_file;
-pub use save_file::SaveData;
+pub use save_file::{SaveData, WorkSpace};
 mod expanded_keycode;
 
 use macroquad::prelude::*;
@@ -1,10 +1,10 @@
-use crate::{node::user_inputs::{shortcut_to_string, string_to_shortcut}, ui::PixelArray};
+use crate::{node::user_inputs::{shortcut_to_string, string_to_shortcut}, palette::Palette, reference::Reference};
 
 use super::*;
 use bimap::Overwritten;
 use serde::{Serialize, Deserialize};
 use serde_json::{from_str, to_string_pretty};
-use std::{collections::{BTreeMap, VecDeque}, env, fmt::Display, fs, path::PathBuf};
+use std::{collections::{BTreeMap, VecDeque}, env, fmt::Display, fs, io::ErrorKind, path::{Path, PathBuf}};
 
 #[derive(Serialize, Deserialize)]
 pub struct SaveDataStoreV1_0_2 {
@@ -12,8 +12,9 @@ pub struct SaveDataStoreV1_0_2 {
     cached_dirs: VecDeque<PathBuf>
 }
 
+/// Workspaces before palettes, only `saved_cols` and `cached_dirs` are carried over
 #[derive(Serialize, Deserialize)]
-pub struct WorkSpaceStore { // IF CHANGING THIS BETWEEN VERSIONS, ADD SUPPORT FOR IT
+pub struct WorkSpaceStoreV1_0_2 {
     saved_cols: Vec<[f32; 4]>,
     current_col: [f32; 4],
     pos: [f32; 2],
@@ -21,24 +22,50 @@ pub struct WorkSpaceStore { // IF CHANGING THIS BETWEEN VERSIONS, ADD SUPPORT FO
     cached_dirs: VecDeque<PathBuf>,
 }
 
+#[derive(Serialize, Deserialize)]
+pub struct WorkSpaceStore { // IF CHANGING THIS BETWEEN VERSIONS, ADD SUPPORT FOR IT
+    palette: Palette,
+    cached_dirs: VecDeque<PathBuf>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    reference: Option<Reference>,
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct SaveDataStore {
     shortcuts: BTreeMap<String, ShortcutInstruction>,
+    #[serde(default = "default_workspace_name")]
+    workspace: String,
+    #[serde(skip)]
+    legacy_cached_dirs: Option<VecDeque<PathBuf>>,
 }
 
-#[derive(Default)]
 pub struct SaveData {
     pub shortcuts: Shortcuts,
-    pub workspaces: Vec<PathBuf>
+    pub workspaces: Vec<PathBuf>,
+    pub workspace_name: String,
+    pub workspace: WorkSpace,
 }
 
+#[derive(Default)]
 pub struct WorkSpace {
-    pixels: Option<PixelArray>,
-    saved_cols: Vec<[f32; 4]>,
-    current_col: [f32; 4],
-    pos: [f32; 2],
-    scale: f32,
-    cached_dirs: VecDeque<PathBuf>,
+    pub palette: Palette,
+    pub cached_dirs: VecDeque<PathBuf>,
+    pub reference: Option<Reference>,
+}
+
+fn default_workspace_name() -> String {
+    "Default".to_string()
+}
+
+impl Default for SaveData {
+    fn default() -> Self {
+        Self {
+            shortcuts: Shortcuts::default(),
+            workspaces: Vec::new(),
+            workspace_name: default_workspace_name(),
+            workspace: WorkSpace::default(),
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -152,33 +179,35 @@ impl ErrorGen {
 
 const FINAL_MESSAGE: &str = "This window does nothing. End the program when you are ready.";
 
-fn get_save_path(error_gen: &ErrorGen) -> Result<PathBuf, Error> {
-    let path = match env::current_exe() {
+fn get_exe_dir(error_gen: &ErrorGen) -> Result<PathBuf, Error> {
+    match env::current_exe() {
         Ok(path) => {
             if let Some(path) = path.parent() {
-                path.to_owned()
+                Ok(path.to_owned())
             } else {
-                return Err(Error::Path { malformed_file: format!("../{path:?}") })
+                Err(Error::Path { malformed_file: format!("../{path:?}") })
             }
         },
-        Err(err) => return Err(error_gen.generic_file_error(err))
-    };
-    Ok(path.join("data.json"))
+        Err(err) => Err(error_gen.generic_file_error(err))
+    }
+}
+
+fn get_save_path(error_gen: &ErrorGen) -> Result<PathBuf, Error> {
+    Ok(get_exe_dir(error_gen)?.join("data.json"))
+}
+
+fn get_workspace_path(error_gen: &ErrorGen, name: &str) -> Result<PathBuf, Error> {
+    if Path::new(name).file_name().is_none_or(|file_name| file_name != name) {
+        return Err(error_gen.path_error())
+    }
+    Ok(get_exe_dir(error_gen)?.join("workspaces").join(name).join("workspace.json"))
 }
 
 fn get_workspaces(error_gen: &ErrorGen) -> Result<Vec<PathBuf>, Error> {
-    let path = match env::current_exe() {
-        Ok(path) => {
-            if let Some(path) = path.parent() {
-                path.to_owned()
-            } else {
-                return Err(Error::Path { malformed_file: format!("../{path:?}") })
-            }
-        },
-        Err(err) => return Err(error_gen.generic_file_error(err))
-    };
+    let path = get_exe_dir(error_gen)?;
     let paths = match fs::read_dir(path.join("workspaces")) {
         Ok(paths) => paths,
+        Err(err) if err.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
         Err(err) => return Err(error_gen.generic_file_error(err))
     };
     let mut result = Vec::new();
@@ -188,6 +217,58 @@ fn get_workspaces(error_gen: &ErrorGen) -> Result<Vec<PathBuf>, Error> {
     Ok(result)
 }
 
+fn load_workspace(error_gen: &mut ErrorGen, name: &str) -> Result<Option<WorkSpace>, Error> {
+    error_gen.set_file(format!("workspaces/{name}/workspace.json"));
+    let path = get_workspace_path(error_gen, name)?;
+
+    let result = match fs::read_to_string(path) {
+        Ok(json) => match from_str::<WorkSpaceStore>(&json) {
+            Ok(store) => Ok(Some(store.into())),
+            Err(err) => match from_str::<WorkSpaceStoreV1_0_2>(&json) {
+                Ok(store) => Ok(Some(store.into())),
+                Err(_) => Err(error_gen.syntax_error(err))
+            }
+        },
+        Err(err) if err.kind() == ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(error_gen.read_error(err))
+    };
+
+    error_gen.reset_file();
+    result
+}
+
+impl WorkSpaceStore {
+    fn from(value: &mut WorkSpace) -> Self {
+        Self {
+            palette: std::mem::take(&mut value.palette),
+            cached_dirs: std::mem::take(&mut value.cached_dirs),
+            reference: value.reference.take()
+        }
+    }
+
+    fn into(self) -> WorkSpace {
+        let mut palette = self.palette;
+        palette.fit_slots();
+        WorkSpace {
+            palette,
+            cached_dirs: self.cached_dirs,
+            reference: self.reference
+        }
+    }
+}
+
+impl WorkSpaceStoreV1_0_2 {
+    fn into(self) -> WorkSpace {
+        let mut palette = Palette::default();
+        palette.set_cols(self.saved_cols);
+        WorkSpace {
+            palette,
+            cached_dirs: self.cached_dirs,
+            reference: None
+        }
+    }
+}
+
 impl SaveDataStore {
     pub fn recover() -> Result<Option<Self>, String> {
         let error_gen = ErrorGen::new();
@@ -202,8 +283,11 @@ impl SaveDataStore {
                 Err(err) => return Err(error_gen.read_error(err).to_string())
             };
 
-            match from_str(&json) {
-                Ok(result) => Ok(Some(result)),
+            match from_str::<Self>(&json) {
+                Ok(mut result) => {
+                    result.legacy_cached_dirs = from_str::<SaveDataStoreV1_0_2>(&json).ok().map(|d| d.cached_dirs);
+                    Ok(Some(result))
+                },
                 Err(err) => Err(error_gen.syntax_error(err).to_string())
             }
         } else {
@@ -214,7 +298,9 @@ impl SaveDataStore {
     fn from(value: &mut SaveData) -> Self {
         let shortcuts = std::mem::take(&mut value.shortcuts.shortcuts);
         Self {
-            shortcuts: shortcuts.into_iter().map(|(shortcut, instruction)| (shortcut_to_string(&shortcut), instruction)).collect()
+            shortcuts: shortcuts.into_iter().map(|(shortcut, instruction)| (shortcut_to_string(&shortcut), instruction)).collect(),
+            workspace: value.workspace_name.clone(),
+            legacy_cached_dirs: None
         }
     }
 
@@ -267,12 +353,22 @@ impl SaveDataStore {
         error_gen.set_file("workspaces/".to_string());
         let workspaces = get_workspaces(&error_gen)?;
 
+        let workspace = match load_workspace(&mut error_gen, &self.workspace)? {
+            Some(workspace) => workspace,
+            None => WorkSpace {
+                cached_dirs: self.legacy_cached_dirs.unwrap_or_default(),
+                ..Default::default()
+            }
+        };
+
         Ok(SaveData {
             shortcuts: Shortcuts {
                 shortcuts,
                 empty: Vec::new()
             },
-            workspaces
+            workspaces,
+            workspace_name: self.workspace,
+            workspace
         })
     }
 }
@@ -283,6 +379,12 @@ impl Drop for SaveData {
 
         let json = to_string_pretty(&SaveDataStore::from(self)).unwrap();
         fs::write(path, json).expect("Unable to write to `data.json` on code end, this is unrecoverable.");
+
+        let path = get_workspace_path(&ErrorGen { file: None }, &self.workspace_name).expect("Unable to access the workspace on code end, this is unrecoverable.");
+        fs::create_dir_all(path.parent().unwrap()).expect("Unable to create the workspace folder on code end, this is unrecoverable.");
+
+        let json = to_string_pretty(&WorkSpaceStore::from(&mut self.workspace)).unwrap();
+        fs::write(path, json).expect("Unable to write to `workspace.json` on code end, this is unrecoverable.");
     }
 }
 
@@ -290,7 +392,13 @@ impl SaveData {
     pub fn recover() -> Result<Self, String> {
         match SaveDataStore::recover()? {
             Some(result) => Ok(result.into()?),
-            None => Ok(Self::default())
+            None => {
+                let mut result = Self::default();
+                if let Some(workspace) = load_workspace(&mut ErrorGen::new(), &result.workspace_name)? {
+                    result.workspace = workspace;
+                }
+                Ok(result)
+            }
         }
     }
 }
\ No newline at end of file
@@ -19,6 +19,18 @@ pub enum ShortcutInstruction { // If changing this, update Deserialize, ORDER, F
     Eraser,
     SaveCol,
     ToggleGrid,
+    ToggleColourPanel,
+    ToggleReference,
+    ToggleSymmetryPanel,
+    ToggleTilePanel,
+    ToggleTransformPanel,
+    ToggleFilterPanel,
+    ToggleOutlinePanel,
+    ToggleImportPanel,
+    Undo,
+    Redo,
+    PreviousFrame,
+    NextFrame,
     ChangePickerType(ColSelection),
     GoTo(Tab)
 }
@@ -55,20 +67,39 @@ impl<'de> Deserialize<'de> for ShortcutInstruction {
     }
 }
 
-const ORDER: [ShortcutInstruction; 13] = [
+const ORDER: [ShortcutInstruction; 32] = [
     ShortcutInstruction::ChangeDrawState(DrawState::Draw),
     ShortcutInstruction::ChangeDrawState(DrawState::Fill),
     ShortcutInstruction::ChangeDrawState(DrawState::Line),
     ShortcutInstruction::ChangeDrawState(DrawState::Picker),
+    ShortcutInstruction::ChangeDrawState(DrawState::Select),
+    ShortcutInstruction::ChangeDrawState(DrawState::Gradient),
     ShortcutInstruction::Eraser,
     ShortcutInstruction::SaveCol,
     ShortcutInstruction::ToggleGrid,
+    ShortcutInstruction::ToggleColourPanel,
+    ShortcutInstruction::ToggleReference,
+    ShortcutInstruction::ToggleSymmetryPanel,
+    ShortcutInstruction::ToggleTilePanel,
+    ShortcutInstruction::ToggleTransformPanel,
+    ShortcutInstruction::ToggleFilterPanel,
+    ShortcutInstruction::ToggleOutlinePanel,
+    ShortcutInstruction::ToggleImportPanel,
+    ShortcutInstruction::Undo,
+    ShortcutInstruction::Redo,
+    ShortcutInstruction::PreviousFrame,
+    ShortcutInstruction::NextFrame,
     ShortcutInstruction::ChangePickerType(ColSelection::Hsva),
     ShortcutInstruction::ChangePickerType(ColSelection::Rgba),
     ShortcutInstruction::ChangePickerType(ColSelection::OkLab),
+    ShortcutInstruction::ChangePickerType(ColSelection::Hsla),
+    ShortcutInstruction::ChangePickerType(ColSelection::OkLch),
+    ShortcutInstruction::ChangePickerType(ColSelection::CieLab),
+    ShortcutInstruction::ChangePickerType(ColSelection::LinearRgba),
     ShortcutInstruction::GoTo(Tab::Draw),
     ShortcutInstruction::GoTo(Tab::Settings),
     ShortcutInstruction::GoTo(Tab::Export),
+    ShortcutInstruction::GoTo(Tab::Palette),
 ];
 
 impl Display for ShortcutInstruction {
@@ -79,6 +110,18 @@ impl Display for ShortcutInstruction {
             Self::Eraser => write!(f, "Eraser"),
             Self::SaveCol => write!(f, "Save colour"),
             Self::ToggleGrid => write!(f, "Toggle grid"),
+            Self::ToggleColourPanel => write!(f, "Toggle colour panel"),
+            Self::ToggleReference => write!(f, "Toggle reference panel"),
+            Self::ToggleSymmetryPanel => write!(f, "Toggle symmetry panel"),
+            Self::ToggleTilePanel => write!(f, "Toggle tile panel"),
+            Self::ToggleTransformPanel => write!(f, "Toggle transform panel"),
+            Self::ToggleFilterPanel => write!(f, "Toggle filter panel"),
+            Self::ToggleOutlinePanel => write!(f, "Toggle outline panel"),
+            Self::ToggleImportPanel => write!(f, "Toggle import panel"),
+            Self::Undo => write!(f, "Undo"),
+            Self::Redo => write!(f, "Redo"),
+            Self::PreviousFrame => write!(f, "Previous frame"),
+            Self::NextFrame => write!(f, "Next frame"),
             Self::ChangePickerType(col_type) => write!(f, "Change picker colour space to {}", col_type),
             Self::GoTo(tab) => write!(f, "Go to tab {}", tab),
         }
@@ -93,6 +136,18 @@ impl FromStr for ShortcutInstruction {
             "Eraser" => Self::Eraser,
             "Save colour" | "Save color" | "Save col" => Self::SaveCol,
             "Toggle grid" => Self::ToggleGrid,
+            "Toggle colour panel" | "Toggle color panel" => Self::ToggleColourPanel,
+            "Toggle reference panel" => Self::ToggleReference,
+            "Toggle symmetry panel" => Self::ToggleSymmetryPanel,
+            "Toggle tile panel" => Self::ToggleTilePanel,
+            "Toggle transform panel" => Self::ToggleTransformPanel,
+            "Toggle filter panel" => Self::ToggleFilterPanel,
+            "Toggle outline panel" => Self::ToggleOutlinePanel,
+            "Toggle import panel" => Self::ToggleImportPanel,
+            "Undo" => Self::Undo,
+            "Redo" => Self::Redo,
+            "Previous frame" => Self::PreviousFrame,
+            "Next frame" => Self::NextFrame,
             
             s if s.starts_with("Change draw state to ") => Self::ChangeDrawState(DrawState::from_str(&s[21..])?),
             s if s.starts_with("Change picker colour space to ") => Self::ChangePickerType(ColSelection::from_str(&s[30..])?),
@@ -154,14 +209,33 @@ impl Default for Shortcuts {
                 (vec![KeyCode::H], ShortcutInstruction::ChangePickerType(ColSelection::Hsva)),
                 (vec![KeyCode::R], ShortcutInstruction::ChangePickerType(ColSelection::Rgba)),
                 (vec![KeyCode::O], ShortcutInstruction::ChangePickerType(ColSelection::OkLab)),
+                (vec![KeyCode::LeftShift, KeyCode::H], ShortcutInstruction::ChangePickerType(ColSelection::Hsla)),
+                (vec![KeyCode::LeftShift, KeyCode::O], ShortcutInstruction::ChangePickerType(ColSelection::OkLch)),
+                (vec![KeyCode::C], ShortcutInstruction::ChangePickerType(ColSelection::CieLab)),
+                (vec![KeyCode::LeftShift, KeyCode::R], ShortcutInstruction::ChangePickerType(ColSelection::LinearRgba)),
                 (vec![KeyCode::F], ShortcutInstruction::ChangeDrawState(DrawState::Fill)),
                 (vec![KeyCode::L], ShortcutInstruction::ChangeDrawState(DrawState::Line)),
                 (vec![KeyCode::D], ShortcutInstruction::ChangeDrawState(DrawState::Draw)),
                 (vec![KeyCode::P], ShortcutInstruction::ChangeDrawState(DrawState::Picker)),
+                (vec![KeyCode::M], ShortcutInstruction::ChangeDrawState(DrawState::Select)),
+                (vec![KeyCode::B], ShortcutInstruction::ChangeDrawState(DrawState::Gradient)),
                 (vec![KeyCode::G], ShortcutInstruction::ToggleGrid),
+                (vec![KeyCode::N], ShortcutInstruction::ToggleColourPanel),
+                (vec![KeyCode::I], ShortcutInstruction::ToggleReference),
+                (vec![KeyCode::Y], ShortcutInstruction::ToggleSymmetryPanel),
+                (vec![KeyCode::T], ShortcutInstruction::ToggleTilePanel),
+                (vec![KeyCode::K], ShortcutInstruction::ToggleTransformPanel),
+                (vec![KeyCode::U], ShortcutInstruction::ToggleFilterPanel),
+                (vec![KeyCode::J], ShortcutInstruction::ToggleOutlinePanel),
+                (vec![KeyCode::V], ShortcutInstruction::ToggleImportPanel),
+                (vec![KeyCode::LeftControl, KeyCode::Z], ShortcutInstruction::Undo),
+                (vec![KeyCode::LeftControl, KeyCode::Y], ShortcutInstruction::Redo),
+                (vec![KeyCode::Comma], ShortcutInstruction::PreviousFrame),
+                (vec![KeyCode::Period], ShortcutInstruction::NextFrame),
                 (vec![KeyCode::LeftControl, KeyCode::D], ShortcutInstruction::GoTo(Tab::Draw)),
                 (vec![KeyCode::LeftControl, KeyCode::S], ShortcutInstruction::GoTo(Tab::Settings)),
                 (vec![KeyCode::LeftControl, KeyCode::E], ShortcutInstruction::GoTo(Tab::Export)),
+                (vec![KeyCode::LeftControl, KeyCode::P], ShortcutInstruction::GoTo(Tab::Palette)),
             ].into_iter().collect(),
             empty: Vec::new()
         }
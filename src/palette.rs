@@ -0,0 +1,163 @@
+use serde::{Deserialize, Serialize};
+
+use crate::colour::ColSelection;
+
+pub mod formats;
+pub mod quantise;
+
+pub const PALETTE_WIDTH: usize = 4;
+pub const DEFAULT_PALETTE_SLOTS: usize = 24;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Swatch {
+    pub col: [f32; 4],
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub name: String,
+}
+
+impl Swatch {
+    pub fn new(col: [f32; 4]) -> Self {
+        Self { col, name: String::new() }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Palette {
+    pub saved_cols: Vec<Option<Swatch>>,
+    #[serde(default)]
+    pub locked: bool,
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Self {
+            saved_cols: vec![None; DEFAULT_PALETTE_SLOTS],
+            locked: false,
+        }
+    }
+}
+
+impl Palette {
+    pub fn cols(&self) -> impl Iterator<Item = [f32; 4]> {
+        self.saved_cols.iter().flatten().map(|swatch| swatch.col)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cols().next().is_none()
+    }
+
+    pub fn contains(&self, col: [f32; 4]) -> bool {
+        self.cols().any(|d| d == col)
+    }
+
+    /// Replaces the palette with `cols`, growing it if needed.
+    pub fn set_cols(&mut self, cols: impl IntoIterator<Item = [f32; 4]>) {
+        self.saved_cols = cols.into_iter().map(|col| Some(Swatch::new(col))).collect();
+        self.fit_slots();
+    }
+
+    /// Adds `cols` after the last filled slot, growing the palette if needed.
+    pub fn append(&mut self, cols: impl IntoIterator<Item = [f32; 4]>) {
+        while self.saved_cols.last().is_some_and(|d| d.is_none()) {
+            self.saved_cols.pop();
+        }
+        self.saved_cols.extend(cols.into_iter().map(|col| Some(Swatch::new(col))));
+        self.fit_slots();
+    }
+
+    /// Pads the palette to whole rows, with at least `DEFAULT_PALETTE_SLOTS` slots and an empty row at the end to grow into.
+    pub fn fit_slots(&mut self) {
+        while self.saved_cols.len() > DEFAULT_PALETTE_SLOTS &&
+            self.saved_cols.len() >= 2 * PALETTE_WIDTH &&
+            self.saved_cols[self.saved_cols.len() - 2 * PALETTE_WIDTH..].iter().all(|d| d.is_none())
+        {
+            self.saved_cols.truncate(self.saved_cols.len() - PALETTE_WIDTH);
+        }
+
+        let len = self.saved_cols.len().next_multiple_of(PALETTE_WIDTH).max(DEFAULT_PALETTE_SLOTS);
+        self.saved_cols.resize(len, None);
+
+        if self.saved_cols[len - PALETTE_WIDTH..].iter().any(|d| d.is_some()) {
+            self.saved_cols.resize(len + PALETTE_WIDTH, None);
+        }
+    }
+
+    /// Moves the swatch at `from` to `to`, shifting the swatches in between.
+    pub fn move_swatch(&mut self, from: usize, to: usize) {
+        if from >= self.saved_cols.len() || to >= self.saved_cols.len() {
+            return;
+        }
+        let swatch = self.saved_cols.remove(from);
+        self.saved_cols.insert(to, swatch);
+        self.fit_slots();
+    }
+
+    /// Returns the palette colour closest to `col`, measured in the `col_sel` colour space.
+    pub fn nearest(&self, col: [f32; 4], col_sel: ColSelection) -> Option<[f32; 4]> {
+        let col = col_sel.col_from_rgba_arr(col);
+        let mut best = None;
+        let mut best_value = f32::INFINITY;
+
+        for saved in self.cols() {
+            let value = col.distance(saved);
+            if value < best_value {
+                best = Some(saved);
+                best_value = value;
+            }
+        }
+
+        best
+    }
+
+    /// Like `nearest`, but leaves the colour alone if the palette isn't locked or is empty.
+    pub fn restrict(&self, col: [f32; 4], col_sel: ColSelection) -> [f32; 4] {
+        if self.locked && !self.contains(col) {
+            self.nearest(col, col_sel).unwrap_or(col)
+        } else {
+            col
+        }
+    }
+}
+
+// Hues that shadows and highlights lean towards in a shading ramp, as fractions of a turn
+const SHADOW_HUE: f32 = 240.0 / 360.0;
+const HIGHLIGHT_HUE: f32 = 60.0 / 360.0;
+
+/// Generates `steps` colours running through `stops`, interpolated in the `col_sel` colour space.
+///
+/// `hue_shift` is the most the hue can be turned, in degrees, with darker colours leaning towards blue and lighter ones towards yellow.
+pub fn ramp(stops: &[[f32; 4]], steps: usize, col_sel: ColSelection, hue_shift: f32) -> Vec<[f32; 4]> {
+    if stops.len() < 2 || steps < 2 {
+        return stops.to_vec();
+    }
+
+    let segments = (stops.len() - 1) as f32;
+    let cols = (0..steps).map(|step| {
+        let t = step as f32 / (steps - 1) as f32 * segments;
+        let index = (t as usize).min(stops.len() - 2);
+        col_sel.gradient(stops[index], stops[index + 1], t - index as f32).map(|d| d.clamp(0.0, 1.0))
+    }).collect::<Vec<_>>();
+
+    let lightness = cols.iter().map(|&col| ColSelection::OkLab.col_from_rgba_arr(col).to_wheel().2).collect::<Vec<_>>();
+    let min = lightness.iter().copied().fold(f32::INFINITY, f32::min);
+    let max = lightness.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+    let half = (max - min) / 2.0;
+    if hue_shift == 0.0 || half <= 0.0 {
+        return cols;
+    }
+
+    cols.into_iter().zip(lightness).map(|(col, l)| {
+        let offset = (l - min - half) / half;
+        let target = if offset < 0.0 { SHADOW_HUE } else { HIGHLIGHT_HUE };
+        shift_hue(col, target, offset.abs() * hue_shift / 360.0)
+    }).collect()
+}
+
+/// Turns the hue of `col` towards `target` by at most `amount`, both as fractions of a turn
+fn shift_hue(col: [f32; 4], target: f32, amount: f32) -> [f32; 4] {
+    let (h, s, v) = ColSelection::Hsva.col_from_rgba_arr(col).to_wheel();
+    let diff = (target - h + 0.5).rem_euclid(1.0) - 0.5;
+    let mut result = ColSelection::Hsva.col_from_wheel(h + diff.clamp(-amount, amount), s, v).to_rgba();
+    result[3] = col[3];
+    result
+}
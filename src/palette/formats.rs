@@ -0,0 +1,378 @@
+use std::{collections::HashMap, fs, path::Path};
+
+use crate::colour::{CieLab, ColType};
+use super::quantise::{self, Method};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PaletteFormat {
+    Gimp,
+    PaintNet,
+    Ase,
+    Jasc,
+    Hex,
+}
+
+impl PaletteFormat {
+    pub const ALL: [PaletteFormat; 5] = [Self::Gimp, Self::PaintNet, Self::Ase, Self::Jasc, Self::Hex];
+
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let ext = path.extension()?.to_string_lossy().to_lowercase();
+        Some(match ext.as_str() {
+            "gpl" => Self::Gimp,
+            "txt" => Self::PaintNet,
+            "ase" => Self::Ase,
+            "pal" => Self::Jasc,
+            "hex" => Self::Hex,
+            _ => return None
+        })
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Gimp => "gpl",
+            Self::PaintNet => "txt",
+            Self::Ase => "ase",
+            Self::Jasc => "pal",
+            Self::Hex => "hex",
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Gimp => "GIMP palette",
+            Self::PaintNet => "Paint.NET palette",
+            Self::Ase => "Adobe swatch exchange",
+            Self::Jasc => "JASC palette",
+            Self::Hex => "Hex list",
+        }
+    }
+}
+
+fn to_u8(value: f32) -> u8 {
+    (value.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+fn from_u8(r: u8, g: u8, b: u8, a: u8) -> [f32; 4] {
+    [r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0, a as f32 / 255.0]
+}
+
+pub fn import(path: &Path) -> Result<Vec<[f32; 4]>, String> {
+    let Some(format) = PaletteFormat::from_path(path) else {
+        return Err(format!("`{}` isn't a supported palette file. Supported extensions are .gpl, .txt, .ase, .pal and .hex.", path.display()))
+    };
+
+    let bytes = fs::read(path).map_err(|err| format!("Error reading `{}`:\n\n{err}", path.display()))?;
+
+    let result = if format == PaletteFormat::Ase {
+        parse_ase(&bytes)
+    } else {
+        let text = String::from_utf8_lossy(&bytes);
+        match format {
+            PaletteFormat::Gimp => parse_gpl(&text),
+            PaletteFormat::PaintNet => parse_paint_net(&text),
+            PaletteFormat::Jasc => parse_jasc(&text),
+            PaletteFormat::Hex => parse_hex(&text),
+            PaletteFormat::Ase => unreachable!()
+        }
+    };
+
+    result.map_err(|err| format!("Error parsing `{}`:\n\n{err}", path.display()))
+}
+
+/// Writes `cols` in the format matching the extension of `path`.
+pub fn export(path: &Path, cols: &[[f32; 4]]) -> Result<(), String> {
+    let Some(format) = PaletteFormat::from_path(path) else {
+        return Err(format!("`{}` doesn't have a supported palette extension.", path.display()))
+    };
+
+    let name = path.file_stem().map(|d| d.to_string_lossy().to_string()).unwrap_or_default();
+    let bytes = match format {
+        PaletteFormat::Gimp => write_gpl(cols, &name).into_bytes(),
+        PaletteFormat::PaintNet => write_paint_net(cols, &name).into_bytes(),
+        PaletteFormat::Ase => write_ase(cols),
+        PaletteFormat::Jasc => write_jasc(cols).into_bytes(),
+        PaletteFormat::Hex => write_hex(cols).into_bytes(),
+    };
+
+    fs::write(path, bytes).map_err(|err| format!("Error writing `{}`:\n\n{err}", path.display()))
+}
+
+/// Most colours a palette extracted from an image can have, any more are merged with median cut
+pub const MAX_EXTRACTED_COLS: usize = 32;
+
+/// The palette of an image file, see `extract`.
+pub fn extract_from_image(path: &Path) -> Result<Vec<[f32; 4]>, String> {
+    let img = image::open(path).map_err(|err| format!("Error loading image `{}`:\n\n{err}", path.display()))?;
+    let img = img.to_rgba8();
+
+    Ok(extract(img.pixels().filter(|p| p.0[3] != 0).map(|p| {
+        let [r, g, b, a] = p.0;
+        from_u8(r, g, b, a)
+    })))
+}
+
+/// Unique colours of `cols`, most common first, or `MAX_EXTRACTED_COLS` colours covering them if there are more than that.
+pub fn extract(cols: impl IntoIterator<Item = [f32; 4]>) -> Vec<[f32; 4]> {
+    let cols = cols.into_iter().collect::<Vec<_>>();
+    let unique = most_common(cols.iter().copied());
+    if unique.len() <= MAX_EXTRACTED_COLS {
+        unique
+    } else {
+        quantise::palette_of(cols, MAX_EXTRACTED_COLS, Method::MedianCut)
+    }
+}
+
+/// Unique colours of `cols`, most common first. Ties keep the order they first appeared in.
+fn most_common(cols: impl IntoIterator<Item = [f32; 4]>) -> Vec<[f32; 4]> {
+    let mut counts: HashMap<[u8; 4], (usize, usize, [f32; 4])> = HashMap::new();
+    for (index, col) in cols.into_iter().enumerate() {
+        let key = col.map(to_u8);
+        counts.entry(key).or_insert((0, index, col)).0 += 1;
+    }
+
+    let mut result = counts.into_values().collect::<Vec<_>>();
+    result.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+    result.into_iter().map(|(_, _, col)| col).collect()
+}
+
+fn parse_channel(value: Option<&str>, line: &str) -> Result<u8, String> {
+    value
+        .and_then(|d| d.parse::<u8>().ok())
+        .ok_or_else(|| format!("Line `{line}` isn't a valid colour."))
+}
+
+fn parse_gpl(text: &str) -> Result<Vec<[f32; 4]>, String> {
+    let mut lines = text.lines();
+    if lines.next().map(|d| d.trim()) != Some("GIMP Palette") {
+        return Err("The file doesn't start with `GIMP Palette`.".to_string())
+    }
+
+    let mut result = Vec::new();
+    for line in lines {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') || trimmed.starts_with("Name:") || trimmed.starts_with("Columns:") {
+            continue;
+        }
+
+        let mut parts = trimmed.split_whitespace();
+        let r = parse_channel(parts.next(), line)?;
+        let g = parse_channel(parts.next(), line)?;
+        let b = parse_channel(parts.next(), line)?;
+        result.push(from_u8(r, g, b, 255));
+    }
+    Ok(result)
+}
+
+fn write_gpl(cols: &[[f32; 4]], name: &str) -> String {
+    let mut result = format!("GIMP Palette\nName: {name}\nColumns: 4\n#\n");
+    for col in cols {
+        let [r, g, b, _] = col.map(to_u8);
+        result.push_str(&format!("{r:>3} {g:>3} {b:>3}\t#{r:02X}{g:02X}{b:02X}\n"));
+    }
+    result
+}
+
+fn parse_hex_col(hex: &str, line: &str) -> Result<[f32; 4], String> {
+    let error = || format!("Line `{line}` isn't a valid hex colour.");
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(error())
+    }
+
+    match hex.len() {
+        6 => {
+            let [_, r, g, b] = u32::from_str_radix(hex, 16).map_err(|_| error())?.to_be_bytes();
+            Ok(from_u8(r, g, b, 255))
+        },
+        8 => {
+            let [r, g, b, a] = u32::from_str_radix(hex, 16).map_err(|_| error())?.to_be_bytes();
+            Ok(from_u8(r, g, b, a))
+        },
+        _ => Err(error())
+    }
+}
+
+fn parse_paint_net(text: &str) -> Result<Vec<[f32; 4]>, String> {
+    let mut result = Vec::new();
+    for line in text.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with(';') {
+            continue;
+        }
+
+        if trimmed.len() != 8 {
+            return Err(format!("Line `{line}` isn't a valid AARRGGBB colour."))
+        }
+        let [a, r, g, b] = parse_hex_col(trimmed, line)?.map(to_u8);
+        result.push(from_u8(r, g, b, a));
+    }
+    Ok(result)
+}
+
+fn write_paint_net(cols: &[[f32; 4]], name: &str) -> String {
+    let mut result = format!(";paint.net Palette File\n;Palette Name: {name}\n;Colors: {}\n", cols.len());
+    for col in cols {
+        let [r, g, b, a] = col.map(to_u8);
+        result.push_str(&format!("{a:02X}{r:02X}{g:02X}{b:02X}\n"));
+    }
+    result
+}
+
+fn parse_jasc(text: &str) -> Result<Vec<[f32; 4]>, String> {
+    let mut lines = text.lines().map(|d| d.trim());
+    if lines.next() != Some("JASC-PAL") {
+        return Err("The file doesn't start with `JASC-PAL`.".to_string())
+    }
+    lines.next();
+    let count = lines
+        .next()
+        .and_then(|d| d.parse::<usize>().ok())
+        .ok_or_else(|| "The colour count on line 3 is missing or invalid.".to_string())?;
+
+    let mut result = Vec::with_capacity(count);
+    for line in lines.filter(|d| !d.is_empty()).take(count) {
+        let mut parts = line.split_whitespace();
+        let r = parse_channel(parts.next(), line)?;
+        let g = parse_channel(parts.next(), line)?;
+        let b = parse_channel(parts.next(), line)?;
+        result.push(from_u8(r, g, b, 255));
+    }
+
+    if result.len() != count {
+        return Err(format!("The file says it has {count} colours, but only {} were found.", result.len()))
+    }
+    Ok(result)
+}
+
+fn write_jasc(cols: &[[f32; 4]]) -> String {
+    let mut result = format!("JASC-PAL\r\n0100\r\n{}\r\n", cols.len());
+    for col in cols {
+        let [r, g, b, _] = col.map(to_u8);
+        result.push_str(&format!("{r} {g} {b}\r\n"));
+    }
+    result
+}
+
+fn parse_hex(text: &str) -> Result<Vec<[f32; 4]>, String> {
+    let mut result = Vec::new();
+    for line in text.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with(';') || trimmed.starts_with("//") {
+            continue;
+        }
+        result.push(parse_hex_col(trimmed.trim_start_matches('#'), line)?);
+    }
+    Ok(result)
+}
+
+fn write_hex(cols: &[[f32; 4]]) -> String {
+    let mut result = String::new();
+    for col in cols {
+        let [r, g, b, a] = col.map(to_u8);
+        if a == 255 {
+            result.push_str(&format!("{r:02x}{g:02x}{b:02x}\n"));
+        } else {
+            result.push_str(&format!("{r:02x}{g:02x}{b:02x}{a:02x}\n"));
+        }
+    }
+    result
+}
+
+struct AseReader<'a> {
+    bytes: &'a [u8],
+    pos: usize
+}
+
+impl<'a> AseReader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], String> {
+        let end = self.pos.checked_add(len).filter(|&end| end <= self.bytes.len())
+            .ok_or_else(|| "The file ends unexpectedly.".to_string())?;
+        let result = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(result)
+    }
+
+    fn u16(&mut self) -> Result<u16, String> {
+        Ok(u16::from_be_bytes(self.take(2)?.try_into().unwrap()))
+    }
+
+    fn u32(&mut self) -> Result<u32, String> {
+        Ok(u32::from_be_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn f32(&mut self) -> Result<f32, String> {
+        Ok(f32::from_be_bytes(self.take(4)?.try_into().unwrap()))
+    }
+}
+
+const ASE_COLOUR_BLOCK: u16 = 0x0001;
+
+fn parse_ase(bytes: &[u8]) -> Result<Vec<[f32; 4]>, String> {
+    let mut reader = AseReader { bytes, pos: 0 };
+    if reader.take(4)? != b"ASEF" {
+        return Err("The file doesn't start with `ASEF`.".to_string())
+    }
+    reader.take(4)?;
+    let blocks = reader.u32()?;
+
+    let mut result = Vec::new();
+    for _ in 0..blocks {
+        let kind = reader.u16()?;
+        let len = reader.u32()? as usize;
+        let block = reader.take(len)?;
+        if kind != ASE_COLOUR_BLOCK {
+            continue;
+        }
+
+        let mut block = AseReader { bytes: block, pos: 0 };
+        let name_len = block.u16()? as usize;
+        block.take(name_len * 2)?;
+        let model = block.take(4)?;
+        let col = match model {
+            b"RGB " => [block.f32()?, block.f32()?, block.f32()?, 1.0],
+            b"Gray" => {
+                let v = block.f32()?;
+                [v, v, v, 1.0]
+            },
+            b"CMYK" => {
+                let (c, m, y, k) = (block.f32()?, block.f32()?, block.f32()?, block.f32()?);
+                [(1.0 - c) * (1.0 - k), (1.0 - m) * (1.0 - k), (1.0 - y) * (1.0 - k), 1.0]
+            },
+            b"LAB " => {
+                let (l, a, b) = (block.f32()?, block.f32()?, block.f32()?);
+                CieLab::new(l * 100.0, a, b, 1.0).to_rgba()
+            },
+            _ => return Err(format!("Unknown colour model `{}`.", String::from_utf8_lossy(model)))
+        };
+        result.push(col.map(|d| d.clamp(0.0, 1.0)));
+    }
+    Ok(result)
+}
+
+fn write_ase(cols: &[[f32; 4]]) -> Vec<u8> {
+    let mut result = Vec::new();
+    result.extend_from_slice(b"ASEF");
+    result.extend_from_slice(&1u16.to_be_bytes());
+    result.extend_from_slice(&0u16.to_be_bytes());
+    result.extend_from_slice(&(cols.len() as u32).to_be_bytes());
+
+    for col in cols {
+        let [r, g, b, _] = col.map(to_u8);
+        let name = format!("#{r:02X}{g:02X}{b:02X}").encode_utf16().chain([0]).collect::<Vec<_>>();
+
+        let mut block = Vec::new();
+        block.extend_from_slice(&(name.len() as u16).to_be_bytes());
+        for unit in name {
+            block.extend_from_slice(&unit.to_be_bytes());
+        }
+        block.extend_from_slice(b"RGB ");
+        for channel in &col[..3] {
+            block.extend_from_slice(&channel.to_be_bytes());
+        }
+        block.extend_from_slice(&2u16.to_be_bytes()); // Normal colour type
+
+        result.extend_from_slice(&ASE_COLOUR_BLOCK.to_be_bytes());
+        result.extend_from_slice(&(block.len() as u32).to_be_bytes());
+        result.extend_from_slice(&block);
+    }
+    result
+}
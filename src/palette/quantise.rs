@@ -0,0 +1,212 @@
+use image::RgbaImage;
+
+use crate::colour::{ColType, OkLab};
+
+const KMEANS_ITERATIONS: usize = 16;
+const BAYER: [[f32; 4]; 4] = [
+    [0.0, 8.0, 2.0, 10.0],
+    [12.0, 4.0, 14.0, 6.0],
+    [3.0, 11.0, 1.0, 9.0],
+    [15.0, 7.0, 13.0, 5.0],
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Method {
+    MedianCut,
+    KMeans,
+}
+
+impl Method {
+    pub const ALL: [Method; 2] = [Self::MedianCut, Self::KMeans];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::MedianCut => "Median cut",
+            Self::KMeans => "K-means",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Dither {
+    None,
+    Ordered,
+    FloydSteinberg,
+}
+
+impl Dither {
+    pub const ALL: [Dither; 3] = [Self::None, Self::Ordered, Self::FloydSteinberg];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::None => "No dither",
+            Self::Ordered => "Ordered",
+            Self::FloydSteinberg => "Floyd-Steinberg",
+        }
+    }
+}
+
+/// An image reduced to a few colours, row by row, `None` where it was see through
+pub struct Quantised {
+    pub width: u32,
+    pub height: u32,
+    pub cols: Vec<Option<[f32; 4]>>,
+}
+
+fn lab(col: [f32; 4]) -> [f32; 3] {
+    OkLab::from_rgba_arr(col).lab()
+}
+
+fn distance(a: [f32; 3], b: [f32; 3]) -> f32 {
+    (a[0] - b[0]).powi(2) + (a[1] - b[1]).powi(2) + (a[2] - b[2]).powi(2)
+}
+
+fn nearest(col: [f32; 3], palette: &[[f32; 3]]) -> usize {
+    (0..palette.len()).min_by(|&a, &b| distance(col, palette[a]).total_cmp(&distance(col, palette[b]))).unwrap_or(0)
+}
+
+fn mean(cols: &[[f32; 3]]) -> [f32; 3] {
+    let sum = cols.iter().fold([0.0; 3], |sum, col| [sum[0] + col[0], sum[1] + col[1], sum[2] + col[2]]);
+    sum.map(|d| d / cols.len() as f32)
+}
+
+/// Keeps splitting the box of colours with the widest spread in half at its median, then takes the mean of each box
+fn median_cut(cols: &[[f32; 3]], n: usize) -> Vec<[f32; 3]> {
+    let spread = |cols: &[[f32; 3]]| -> (usize, f32) {
+        (0..3).map(|axis| {
+            let (min, max) = cols.iter().fold((f32::MAX, f32::MIN), |(min, max), col| (min.min(col[axis]), max.max(col[axis])));
+            (axis, max - min)
+        }).max_by(|a, b| a.1.total_cmp(&b.1)).unwrap()
+    };
+
+    let mut boxes = vec![cols.to_vec()];
+    while boxes.len() < n {
+        let Some((index, axis)) = boxes.iter().enumerate()
+            .filter(|(_, cols)| cols.len() > 1)
+            .map(|(index, cols)| (index, spread(cols)))
+            .filter(|(_, (_, range))| *range > 0.0)
+            .max_by(|a, b| a.1.1.total_cmp(&b.1.1))
+            .map(|(index, (axis, _))| (index, axis))
+        else { break };
+
+        let mut cols = boxes.swap_remove(index);
+        cols.sort_by(|a, b| a[axis].total_cmp(&b[axis]));
+        let upper = cols.split_off(cols.len() / 2);
+        boxes.push(cols);
+        boxes.push(upper);
+    }
+
+    boxes.iter().map(|cols| mean(cols)).collect()
+}
+
+/// Starts from the median cut and moves each colour to the mean of the colours nearest to it
+fn k_means(cols: &[[f32; 3]], n: usize) -> Vec<[f32; 3]> {
+    let mut centres = median_cut(cols, n);
+    for _ in 0..KMEANS_ITERATIONS {
+        let mut clusters = vec![Vec::new(); centres.len()];
+        for col in cols {
+            clusters[nearest(*col, &centres)].push(*col);
+        }
+
+        let mut moved = false;
+        for (centre, cluster) in centres.iter_mut().zip(&clusters) {
+            // An empty cluster keeps its colour rather than vanishing
+            if cluster.is_empty() {
+                continue;
+            }
+            let new = mean(cluster);
+            moved |= distance(new, *centre) > 1e-8;
+            *centre = new;
+        }
+        if !moved {
+            break;
+        }
+    }
+    centres
+}
+
+/// `n` colours picked in OkLab to best cover the visible pixels of `image`
+pub fn palette(image: &RgbaImage, n: usize, method: Method) -> Vec<[f32; 4]> {
+    palette_of(image.pixels().map(|pixel| pixel.0.map(|d| d as f32 / 255.0)), n, method)
+}
+
+/// Like `palette`, for any colours rather than an image
+pub fn palette_of(cols: impl IntoIterator<Item = [f32; 4]>, n: usize, method: Method) -> Vec<[f32; 4]> {
+    let cols = cols.into_iter()
+        .filter(|col| col[3] >= 0.5)
+        .map(lab)
+        .collect::<Vec<_>>();
+    if cols.is_empty() {
+        return Vec::new();
+    }
+
+    let centres = match method {
+        Method::MedianCut => median_cut(&cols, n.max(1)),
+        Method::KMeans => k_means(&cols, n.max(1)),
+    };
+    let mut result = centres.into_iter()
+        .map(|[l, a, b]| OkLab::new(l, a, b, 1.0).to_rgba().map(|d| d.clamp(0.0, 1.0)))
+        .collect::<Vec<_>>();
+    // Darkest first, which is how most palettes are laid out
+    result.sort_by(|a, b| lab(*a)[0].total_cmp(&lab(*b)[0]));
+    result.dedup();
+    result
+}
+
+/// Replaces every visible pixel of `image` with the nearest colour of `palette`, in OkLab.
+/// Pixels less than half opaque are left out.
+pub fn remap(image: &RgbaImage, palette: &[[f32; 4]], dither: Dither) -> Quantised {
+    let (width, height) = image.dimensions();
+    let targets = palette.iter().map(|col| lab(*col)).collect::<Vec<_>>();
+    let mut cols = vec![None; (width * height) as usize];
+    if targets.is_empty() {
+        return Quantised { width, height, cols };
+    }
+
+    // Ordered dithering nudges lightness by about the gap between neighbouring palette colours
+    let spread = if targets.len() > 1 {
+        targets.iter().enumerate().map(|(index, col)| {
+            targets.iter().enumerate()
+                .filter(|(other, _)| *other != index)
+                .map(|(_, other)| distance(*col, *other).sqrt())
+                .fold(f32::MAX, f32::min)
+        }).sum::<f32>() / targets.len() as f32
+    } else {
+        0.0
+    };
+
+    let mut source = image.pixels()
+        .map(|pixel| (pixel.0[3] >= 128).then(|| lab(pixel.0.map(|d| d as f32 / 255.0))))
+        .collect::<Vec<_>>();
+
+    for y in 0..height {
+        for x in 0..width {
+            let index = (y * width + x) as usize;
+            let Some(mut col) = source[index] else { continue };
+
+            if dither == Dither::Ordered {
+                col[0] += (BAYER[y as usize % 4][x as usize % 4] / 16.0 - 0.5) * spread;
+            }
+            let chosen = nearest(col, &targets);
+            cols[index] = Some(palette[chosen]);
+
+            if dither == Dither::FloydSteinberg {
+                let error = [0, 1, 2].map(|axis| col[axis] - targets[chosen][axis]);
+                let spill = [(1, 0, 7.0), (-1, 1, 3.0), (0, 1, 5.0), (1, 1, 1.0)];
+                for (dx, dy, weight) in spill {
+                    let (nx, ny) = (x as i64 + dx, y as i64 + dy);
+                    if nx < 0 || nx >= width as i64 || ny >= height as i64 {
+                        continue;
+                    }
+                    if let Some(next) = &mut source[(ny as u32 * width + nx as u32) as usize] {
+                        for axis in 0..3 {
+                            next[axis] += error[axis] * weight / 16.0;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    Quantised { width, height, cols }
+}
@@ -0,0 +1,96 @@
+use std::path::{Path, PathBuf};
+
+use macroquad::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::transform::{Transform, WorldPos};
+
+/// A picture to trace over. It's never edited, only drawn with its top left at `pos` and each of its pixels `scale` pixels wide
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Reference {
+    pub path: PathBuf,
+    pub pos: [f32; 2],
+    pub scale: f32,
+    pub opacity: f32,
+    #[serde(default)]
+    pub locked: bool,
+    #[serde(default = "default_true")]
+    pub visible: bool,
+    #[serde(default)]
+    pub above: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl Reference {
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            pos: [0.0, 0.0],
+            scale: 1.0,
+            opacity: 0.5,
+            locked: false,
+            visible: true,
+            above: false,
+        }
+    }
+}
+
+/// The pixels of a `Reference`, kept apart from it since only the path is saved
+pub struct ReferenceImage {
+    pub path: PathBuf,
+    image: Image,
+    texture: Texture2D,
+}
+
+impl ReferenceImage {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let img = image::open(path).map_err(|err| format!("Error loading image `{}`:\n\n{err}", path.display()))?;
+        let img = img.to_rgba8();
+
+        let image = Image {
+            width: img.width() as u16,
+            height: img.height() as u16,
+            bytes: img.into_raw(),
+        };
+        let texture = Texture2D::from_image(&image);
+        texture.set_filter(FilterMode::Nearest);
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            image,
+            texture,
+        })
+    }
+
+    pub fn draw(&self, reference: &Reference, transform: &Transform) {
+        let pos = transform.world_to_screen(&WorldPos(reference.pos[0], reference.pos[1]));
+        let size = reference.scale * transform.size();
+
+        draw_texture_ex(
+            &self.texture,
+            pos.0,
+            pos.1,
+            Color::new(1.0, 1.0, 1.0, reference.opacity),
+            DrawTextureParams {
+                dest_size: Some(vec2(self.image.width as f32 * size, self.image.height as f32 * size)),
+                ..Default::default()
+            }
+        );
+    }
+
+    /// The colour under the centre of the drawing pixel at `pos`, ignoring the opacity it's drawn with.
+    /// Fully see through pixels count as nothing being there
+    pub fn sample(&self, reference: &Reference, pos: [i16; 2]) -> Option<[f32; 4]> {
+        let x = ((pos[0] as f32 + 0.5 - reference.pos[0]) / reference.scale).floor();
+        let y = ((pos[1] as f32 + 0.5 - reference.pos[1]) / reference.scale).floor();
+        if x < 0.0 || y < 0.0 || x >= self.image.width as f32 || y >= self.image.height as f32 {
+            return None;
+        }
+
+        let col = self.image.get_pixel(x as u32, y as u32);
+        (col.a > 0.0).then_some([col.r, col.g, col.b, col.a])
+    }
+}
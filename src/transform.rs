@@ -4,6 +4,10 @@ use super::bresenham::Bresenham;
 
 use super::colour::{ColType, Rgba};
 
+mod geometry;
+mod effects;
+pub use geometry::Geometric;
+
 #[derive(Clone, Copy, Debug, Default)]
 pub struct WorldPos(pub f32, pub f32);
 
@@ -50,7 +54,14 @@ impl Eq for Pixel {}
 pub struct PixelArray {
     pixels: HashSet<Pixel>,
     pub grid_col: [f32; 4],
-    pub crossboard_col: [f32; 4]
+    pub crossboard_col: [f32; 4],
+    /// When set, the drawing tools wrap into the tile of this size with its top left at 0, 0
+    pub wrap: Option<[i16; 2]>,
+}
+
+/// Whether `pos` is inside the `region` corners, where no region covers everything
+fn in_region(region: Option<[[i16; 2]; 2]>, pos: [i16; 2]) -> bool {
+    region.is_none_or(|[min, max]| (min[0]..=max[0]).contains(&pos[0]) && (min[1]..=max[1]).contains(&pos[1]))
 }
 
 fn try_from_f32_to_i16(float: f32) -> Option<i16> {
@@ -152,12 +163,21 @@ impl Default for PixelArray {
         Self {
             pixels: HashSet::new(),
             grid_col,
-            crossboard_col
+            crossboard_col,
+            wrap: None,
         }
     }
 }
 
 impl PixelArray {
+    /// `pos` moved into the tile if edits are wrapping
+    pub fn wrap_pos(&self, pos: [i16; 2]) -> [i16; 2] {
+        match self.wrap {
+            Some([w, h]) => [pos[0].rem_euclid(w), pos[1].rem_euclid(h)],
+            None => pos
+        }
+    }
+
     pub fn insert(&mut self, pixel: Pixel) {
         self.pixels.take(&pixel);
         self.pixels.insert(pixel);
@@ -167,23 +187,55 @@ impl PixelArray {
         self.pixels.take(&Pixel { pos, col: [0.0; 4] });
     }
 
+    /// Sets `pos` to `col`, or erases it for `None`, wrapping it into the tile like the drawing tools do
+    pub fn paint(&mut self, pos: [i16; 2], col: Option<[f32; 4]>) {
+        let pos = self.wrap_pos(pos);
+        match col {
+            Some(col) => self.insert(Pixel { pos, col }),
+            None => self.remove(pos),
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.pixels.clear();
+    }
+
     pub fn get(&self, pos: [i16; 2]) -> Option<&Pixel> {
         self.pixels.get(&Pixel { pos, col: [0.0; 4] })
     }
 
+    /// Like `get`, but an empty position reads the pixel it wraps onto, so the copies round the tile can be picked from
+    pub fn get_wrapped(&self, pos: [i16; 2]) -> Option<&Pixel> {
+        self.get(pos).or_else(|| self.get(self.wrap_pos(pos)))
+    }
+
     pub fn get_at_mouse(&self, pos: Vec2, transform: &Transform) -> Option<&Pixel> {
         let pos = transform.get_int_pos(pos)?;
-        self.get(pos)
+        self.get_wrapped(pos)
     }
 
     pub fn draw(&self, transform: &Transform, grid_lines: bool, crossboard: bool) {
         if crossboard {
             self.draw_crossboard(transform)
         }
+        self.draw_pixels(transform, crossboard);
+        if grid_lines {
+            self.draw_grid_lines(transform)
+        }
+    }
 
+    /// Draws only the pixels, without the background or grid around them
+    pub fn draw_pixels(&self, transform: &Transform, crossboard: bool) {
         for pixel in &self.pixels {
             let Some(pos) = transform.world_to_screen_filter(&WorldPos(pixel.pos[0] as f32, pixel.pos[1] as f32)) else { continue };
             let size = transform.size();
+            // Translucent pixels go over their own checkerboard so it's clear how see through they are
+            if crossboard && pixel.col[3] < 1.0 {
+                let half = size / 2.0;
+                draw_rectangle(pos.0, pos.1, size, size, WHITE);
+                draw_rectangle(pos.0, pos.1, half, half, LIGHTGRAY);
+                draw_rectangle(pos.0 + half, pos.1 + half, half, half, LIGHTGRAY);
+            }
             draw_rectangle(
                 pos.0,
                 pos.1,
@@ -197,8 +249,25 @@ impl PixelArray {
                 ),
             );
         }
-        if grid_lines {
-            self.draw_grid_lines(transform)
+    }
+
+    /// Draws the pixels faded towards `tint`, to show the frames either side of the one being drawn
+    pub fn draw_onion(&self, transform: &Transform, tint: [f32; 3]) {
+        for pixel in &self.pixels {
+            let Some(pos) = transform.world_to_screen_filter(&WorldPos(pixel.pos[0] as f32, pixel.pos[1] as f32)) else { continue };
+            let size = transform.size();
+            draw_rectangle(
+                pos.0,
+                pos.1,
+                size,
+                size,
+                Color::new(
+                    (pixel.col[0] + tint[0]) / 2.0,
+                    (pixel.col[1] + tint[1]) / 2.0,
+                    (pixel.col[2] + tint[2]) / 2.0,
+                    pixel.col[3] * 0.3,
+                ),
+            );
         }
     }
 
@@ -330,9 +399,15 @@ impl PixelArray {
         ]
     }
 
-    pub fn fill(&mut self, pos: [i16; 2], col: Option<[f32; 4]>) {
+    /// The positions a fill at `pos` would cover, empty if the area isn't enclosed
+    /// When wrapping, the region can run off one edge of the tile and back in the other, so it's always enclosed
+    pub fn fill_region(&self, pos: [i16; 2]) -> HashSet<[i16; 2]> {
         let [WorldPos(minx, miny), WorldPos(maxx, maxy)] = self.get_bounds();
-        let (minx, miny, maxx, maxy) = (minx as i16, miny as i16, maxx as i16, maxy as i16);
+        let (minx, miny, maxx, maxy) = match self.wrap {
+            Some([w, h]) => (0, 0, w - 1, h - 1),
+            None => (minx as i16, miny as i16, maxx as i16, maxy as i16)
+        };
+        let pos = self.wrap_pos(pos);
         let old_col = self.get(pos).map(|p| p.col);
 
         let mut additions = HashSet::new();
@@ -349,6 +424,7 @@ impl PixelArray {
             }
             
             for pos in [[x+1, y], [x, y+1], [x-1, y], [x, y-1]] {
+                let pos = self.wrap_pos(pos);
                 if !checked.contains(&pos) && self.get(pos).map(|p| p.col) == old_col {
                     unchecked.push(pos);
                 }
@@ -359,38 +435,47 @@ impl PixelArray {
             checked.insert(pos);
         }
 
-        match col {
-            Some(col) => {
-                for pos in additions {
-                    self.insert(Pixel { pos, col });
-                }
-            },
-            None => {
-                for pos in additions {
-                    self.remove(pos);
-                }
-            }
+        additions
+    }
+
+    pub fn fill(&mut self, pos: [i16; 2], col: Option<[f32; 4]>) {
+        for pos in self.fill_region(pos) {
+            self.paint(pos, col);
         }
     }
 
     pub fn line(&mut self, start: [i16; 2], end: [i16; 2], col: Option<[f32; 4]>) {
-        match col {
-            Some(col) => {
-                for pos in Bresenham::new(start, end) {
-                    self.insert(Pixel { pos, col });
-                }
-            },
-            None => {
-                for pos in Bresenham::new(start, end) {
-                    self.remove(pos)
-                }
-            }
+        for pos in Bresenham::new(start, end) {
+            self.paint(pos, col);
         }
     }
 
     pub fn iter(&self) -> impl Iterator<Item = &Pixel> {
         self.pixels.iter()
     }
+
+    pub fn map_cols<F: FnMut([f32; 4]) -> [f32; 4]>(&mut self, mut f: F) {
+        self.pixels = std::mem::take(&mut self.pixels)
+            .into_iter()
+            .map(|pixel| Pixel { pos: pixel.pos, col: f(pixel.col) })
+            .collect();
+    }
+
+    /// The colours of the pixels inside the `region` corners, or of every pixel when there are none
+    pub fn cols_in(&self, region: Option<[[i16; 2]; 2]>) -> impl Iterator<Item = [f32; 4]> {
+        self.pixels.iter().filter(move |pixel| in_region(region, pixel.pos)).map(|pixel| pixel.col)
+    }
+
+    /// Like `map_cols`, but only for pixels inside the `region` corners when there are some
+    pub fn map_cols_in<F: FnMut([f32; 4]) -> [f32; 4]>(&mut self, region: Option<[[i16; 2]; 2]>, mut f: F) {
+        self.pixels = std::mem::take(&mut self.pixels)
+            .into_iter()
+            .map(|pixel| {
+                let inside = in_region(region, pixel.pos);
+                Pixel { pos: pixel.pos, col: if inside { f(pixel.col) } else { pixel.col } }
+            })
+            .collect();
+    }
 }
 
 impl WorldPos {
@@ -0,0 +1,55 @@
+use std::collections::HashSet;
+
+use super::{Pixel, PixelArray, in_region};
+
+const SIDES: [[i16; 2]; 4] = [[1, 0], [-1, 0], [0, 1], [0, -1]];
+const DIAGONALS: [[i16; 2]; 4] = [[1, 1], [1, -1], [-1, 1], [-1, -1]];
+
+impl PixelArray {
+    /// Positions of the pixels inside the `region` corners, or all of them without one, that can be seen
+    fn opaque(&self, region: Option<[[i16; 2]; 2]>) -> HashSet<[i16; 2]> {
+        self.pixels.iter()
+            .filter(|pixel| pixel.col[3] > 0.0)
+            .filter(|pixel| in_region(region, pixel.pos))
+            .map(|pixel| pixel.pos)
+            .collect()
+    }
+
+    fn is_empty_at(&self, pos: [i16; 2]) -> bool {
+        self.get(pos).is_none_or(|pixel| pixel.col[3] == 0.0)
+    }
+
+    /// Draws a 1px line of `col` round the opaque pixels, on the empty pixels next to them, or over their own edge if `inside`.
+    /// Diagonal neighbours count as touching when `diagonal`.
+    pub fn outline(&mut self, col: [f32; 4], diagonal: bool, inside: bool, region: Option<[[i16; 2]; 2]>) {
+        let opaque = self.opaque(region);
+        let neighbours = if diagonal { [SIDES, DIAGONALS].concat() } else { SIDES.to_vec() };
+        let mut edge = HashSet::new();
+
+        for pos in &opaque {
+            for offset in &neighbours {
+                let (Some(x), Some(y)) = (pos[0].checked_add(offset[0]), pos[1].checked_add(offset[1])) else { continue };
+                if !self.is_empty_at([x, y]) {
+                    continue;
+                }
+                edge.insert(if inside { *pos } else { [x, y] });
+            }
+        }
+
+        for pos in edge {
+            self.insert(Pixel { pos, col });
+        }
+    }
+
+    /// Puts a copy of the opaque pixels' shape in `col` at `offset`, only where it would be seen behind them
+    pub fn drop_shadow(&mut self, col: [f32; 4], offset: [i16; 2], region: Option<[[i16; 2]; 2]>) {
+        let shadow = self.opaque(region).into_iter()
+            .filter_map(|pos| Some([pos[0].checked_add(offset[0])?, pos[1].checked_add(offset[1])?]))
+            .filter(|&pos| self.is_empty_at(pos))
+            .collect::<Vec<_>>();
+
+        for pos in shadow {
+            self.insert(Pixel { pos, col });
+        }
+    }
+}
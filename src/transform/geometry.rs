@@ -0,0 +1,205 @@
+use std::collections::HashMap;
+
+use super::{Pixel, PixelArray, in_region};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Geometric {
+    FlipHorizontal,
+    FlipVertical,
+    Rotate90,
+    Rotate180,
+    Rotate270,
+    /// Clockwise by any angle in degrees, either nearest neighbour or RotSprite
+    Rotate { degrees: f32, rotsprite: bool },
+    ScaleUp(u8),
+    ScaleDown(u8),
+    Translate([i16; 2]),
+}
+
+type Grid = HashMap<[i32; 2], [f32; 4]>;
+
+/// Most pixels an area can cover to be rotated by any angle. Every pixel the result could reach is sampled, so this bounds the time taken.
+const MAX_ROTATE_AREA: u64 = 1024 * 1024;
+/// RotSprite keeps a copy 64 times the size of the area, so it gets a much smaller limit
+const MAX_ROTSPRITE_AREA: u64 = 128 * 128;
+
+/// Rows of cells, `None` where there's no pixel
+struct Dense {
+    w: i32,
+    h: i32,
+    cells: Vec<Option<[f32; 4]>>,
+}
+
+impl Dense {
+    fn get(&self, x: i32, y: i32) -> Option<[f32; 4]> {
+        if (0..self.w).contains(&x) && (0..self.h).contains(&y) {
+            self.cells[(y * self.w + x) as usize]
+        } else {
+            None
+        }
+    }
+
+    /// Doubles the size with the Scale2x rules, which rounds off diagonal edges instead of leaving steps
+    fn scale2x(&self) -> Self {
+        let same = |a: Option<[f32; 4]>, b: Option<[f32; 4]>| a.map(|d| d.map(f32::to_bits)) == b.map(|d| d.map(f32::to_bits));
+        let mut cells = vec![None; self.w as usize * self.h as usize * 4];
+        let w = self.w * 2;
+
+        for y in 0..self.h {
+            for x in 0..self.w {
+                let p = self.get(x, y);
+                let a = self.get(x, y - 1);
+                let b = self.get(x + 1, y);
+                let c = self.get(x - 1, y);
+                let d = self.get(x, y + 1);
+
+                let corners = [
+                    if same(c, a) && !same(c, d) && !same(a, b) { a } else { p },
+                    if same(a, b) && !same(a, c) && !same(b, d) { b } else { p },
+                    if same(d, c) && !same(d, b) && !same(c, a) { c } else { p },
+                    if same(b, d) && !same(b, a) && !same(d, c) { d } else { p },
+                ];
+                for (index, col) in corners.into_iter().enumerate() {
+                    let (dx, dy) = (index as i32 % 2, index as i32 / 2);
+                    cells[((y * 2 + dy) * w + x * 2 + dx) as usize] = col;
+                }
+            }
+        }
+
+        Self { w, h: self.h * 2, cells }
+    }
+}
+
+fn rotate(source: &Grid, min: [i32; 2], max: [i32; 2], degrees: f32, rotsprite: bool) -> Grid {
+    let (sin, cos) = degrees.to_radians().sin_cos();
+    let centre = [(min[0] + max[0] + 1) as f32 / 2.0, (min[1] + max[1] + 1) as f32 / 2.0];
+
+    let (w, h) = (max[0] - min[0] + 1, max[1] - min[1] + 1);
+
+    // RotSprite samples an 8x Scale2x copy, so the rotated edges keep the shape of the original.
+    // Nearest neighbour reads the pixels as they are.
+    let upscale = if rotsprite { 8 } else { 1 };
+    let dense = rotsprite.then(|| {
+        let cells = (0..h).flat_map(|y| (0..w).map(move |x| [x + min[0], y + min[1]])).map(|pos| source.get(&pos).copied()).collect();
+        Dense { w, h, cells }.scale2x().scale2x().scale2x()
+    });
+    let sample = |cell: [i32; 2]| match &dense {
+        Some(dense) => dense.get(cell[0], cell[1]),
+        None => source.get(&[cell[0] + min[0], cell[1] + min[1]]).copied(),
+    };
+
+    // Every corner of the area is rotated to find how far the result can reach
+    let half = [w as f32 / 2.0, h as f32 / 2.0];
+    let reach = (half[0] * cos).abs() + (half[1] * sin).abs();
+    let reach = [reach, (half[0] * sin).abs() + (half[1] * cos).abs()];
+    let from = [(centre[0] - reach[0]).floor() as i32, (centre[1] - reach[1]).floor() as i32];
+    let to = [(centre[0] + reach[0]).ceil() as i32, (centre[1] + reach[1]).ceil() as i32];
+
+    let mut result = Grid::new();
+    for y in from[1]..to[1] {
+        for x in from[0]..to[0] {
+            // Turning each destination centre back onto the source means every destination pixel gets exactly one sample
+            let dx = x as f32 + 0.5 - centre[0];
+            let dy = y as f32 + 0.5 - centre[1];
+            let sx = dx * cos + dy * sin + centre[0] - min[0] as f32;
+            let sy = -dx * sin + dy * cos + centre[1] - min[1] as f32;
+
+            let cell = [(sx * upscale as f32).floor() as i32, (sy * upscale as f32).floor() as i32];
+            if let Some(col) = sample(cell) {
+                result.insert([x, y], col);
+            }
+        }
+    }
+    result
+}
+
+/// Each `n` by `n` block becomes its most common colour, or nothing if most of it was empty
+fn scale_down(source: &Grid, min: [i32; 2], n: i32) -> Grid {
+    let mut blocks = HashMap::<[i32; 2], Vec<[f32; 4]>>::new();
+    for (pos, col) in source {
+        let block = [(pos[0] - min[0]).div_euclid(n), (pos[1] - min[1]).div_euclid(n)];
+        blocks.entry(block).or_default().push(*col);
+    }
+
+    blocks.into_iter().filter(|(_, cols)| cols.len() * 2 >= (n * n) as usize).map(|(block, cols)| {
+        let mut counts = HashMap::<[u32; 4], (usize, [f32; 4])>::new();
+        for col in cols {
+            counts.entry(col.map(f32::to_bits)).or_insert((0, col)).0 += 1;
+        }
+        let col = counts.into_values().max_by_key(|(count, _)| *count).unwrap().1;
+        ([min[0] + block[0], min[1] + block[1]], col)
+    }).collect()
+}
+
+impl PixelArray {
+    /// Applies `op` to the pixels inside the `region` corners, or the whole drawing without one.
+    /// Returns the corners of where the pixels ended up, so a selection can follow them.
+    /// Fails without changing anything if the area is too big to rotate by `op`'s angle.
+    pub fn transform(&mut self, op: Geometric, region: Option<[[i16; 2]; 2]>) -> Result<Option<[[i16; 2]; 2]>, String> {
+        let taken = self.pixels.iter().filter(|pixel| in_region(region, pixel.pos)).copied().collect::<Vec<_>>();
+        if taken.is_empty() {
+            return Ok(region);
+        }
+
+        let [min, max] = region.unwrap_or_else(|| {
+            let mut min = taken[0].pos;
+            let mut max = taken[0].pos;
+            for pixel in &taken {
+                min = [min[0].min(pixel.pos[0]), min[1].min(pixel.pos[1])];
+                max = [max[0].max(pixel.pos[0]), max[1].max(pixel.pos[1])];
+            }
+            [min, max]
+        });
+        let min = min.map(|d| d as i32);
+        let max = max.map(|d| d as i32);
+
+        if let Geometric::Rotate { rotsprite, .. } = op {
+            let area = (max[0] - min[0] + 1) as u64 * (max[1] - min[1] + 1) as u64;
+            let (limit, method) = if rotsprite { (MAX_ROTSPRITE_AREA, "RotSprite") } else { (MAX_ROTATE_AREA, "Nearest") };
+            if area > limit {
+                return Err(format!(
+                    "{} x {} is too big for {method} rotation, the most is {limit} pixels.",
+                    max[0] - min[0] + 1,
+                    max[1] - min[1] + 1
+                ));
+            }
+        }
+        // Twice the centre, so turning about it stays in whole numbers
+        let (sx, sy) = (min[0] + max[0], min[1] + max[1]);
+
+        for pixel in &taken {
+            self.pixels.remove(pixel);
+        }
+        let source = taken.iter().map(|pixel| (pixel.pos.map(|d| d as i32), pixel.col)).collect::<Grid>();
+        let map = |f: &dyn Fn(i32, i32) -> [i32; 2]| source.iter().map(|(&[x, y], &col)| (f(x, y), col)).collect::<Grid>();
+
+        let result = match op {
+            Geometric::FlipHorizontal => map(&|x, y| [sx - x, y]),
+            Geometric::FlipVertical => map(&|x, y| [x, sy - y]),
+            Geometric::Rotate90 => map(&|x, y| [(sx + sy - 2 * y).div_euclid(2), (sy - sx + 2 * x).div_euclid(2)]),
+            Geometric::Rotate180 => map(&|x, y| [sx - x, sy - y]),
+            Geometric::Rotate270 => map(&|x, y| [(sx - sy + 2 * y).div_euclid(2), (sy + sx - 2 * x).div_euclid(2)]),
+            Geometric::Rotate { degrees, rotsprite } => rotate(&source, min, max, degrees, rotsprite),
+            Geometric::ScaleUp(n) => {
+                let n = n.max(1) as i32;
+                source.iter().flat_map(|(&[x, y], &col)| {
+                    let base = [min[0] + (x - min[0]) * n, min[1] + (y - min[1]) * n];
+                    (0..n * n).map(move |i| ([base[0] + i % n, base[1] + i / n], col))
+                }).collect()
+            },
+            Geometric::ScaleDown(n) => scale_down(&source, min, n.max(1) as i32),
+            Geometric::Translate([dx, dy]) => map(&|x, y| [x + dx as i32, y + dy as i32]),
+        };
+
+        let mut corners: Option<[[i16; 2]; 2]> = None;
+        for (pos, col) in result {
+            let (Ok(x), Ok(y)) = (i16::try_from(pos[0]), i16::try_from(pos[1])) else { continue };
+            self.insert(Pixel { pos: [x, y], col });
+            corners = Some(match corners {
+                Some([min, max]) => [[min[0].min(x), min[1].min(y)], [max[0].max(x), max[1].max(y)]],
+                None => [[x, y], [x, y]],
+            });
+        }
+        Ok(corners)
+    }
+}
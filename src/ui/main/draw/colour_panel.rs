@@ -0,0 +1,221 @@
+use macroquad::miniquad::window::clipboard_set;
+
+use super::*;
+
+const SPACES: [(&str, [&str; 3]); 3] = [
+    ("RGB", ["R", "G", "B"]),
+    ("HSV", ["H", "S", "V"]),
+    ("Ok Lab", ["L", "a", "b"]),
+];
+// The alpha field comes after the nine channel fields
+const ALPHA_FIELD: usize = 9;
+
+/// The value of every field, in the units they're shown in
+fn field_values(col: [f32; 4]) -> [f32; 10] {
+    let [r, g, b, a] = col;
+    let (h, s, v) = ColSelection::Hsva.col_from_rgba_arr(col).to_wheel();
+    let [l, ok_a, ok_b] = OkLab::from_rgba_arr(col).lab();
+
+    [
+        r * 255.0, g * 255.0, b * 255.0,
+        h * 360.0, s * 100.0, v * 100.0,
+        l, ok_a, ok_b,
+        a * 255.0,
+    ]
+}
+
+/// Rebuilds the colour after `field` was set to `value`, keeping the rest of its colour space as it was
+fn set_field(col: [f32; 4], field: usize, value: f32) -> [f32; 4] {
+    let mut values = field_values(col);
+    values[field] = value;
+    let alpha = (values[ALPHA_FIELD] / 255.0).clamp(0.0, 1.0);
+
+    let mut result = match field / 3 {
+        0 => [values[0] / 255.0, values[1] / 255.0, values[2] / 255.0, alpha],
+        1 => ColSelection::Hsva.col_from_wheel(values[3] / 360.0, (values[4] / 100.0).clamp(0.0, 1.0), (values[5] / 100.0).clamp(0.0, 1.0)).to_rgba(),
+        2 => OkLab::new(values[6].clamp(0.0, 1.0), values[7], values[8], alpha).to_rgba(),
+        _ => col,
+    };
+    result = result.map(|d| d.clamp(0.0, 1.0));
+    result[3] = alpha;
+    result
+}
+
+fn format_value(field: usize, value: f32) -> String {
+    if field / 3 == 2 {
+        format!("{value:.3}")
+    } else {
+        format!("{}", value.round())
+    }
+}
+
+fn css_rgb(col: [f32; 4]) -> String {
+    let [r, g, b, a] = col.map(|d| (d * 255.0).round());
+    if a == 255.0 {
+        format!("rgb({r} {g} {b})")
+    } else {
+        format!("rgb({r} {g} {b} / {:.0}%)", col[3] * 100.0)
+    }
+}
+
+fn css_oklab(col: [f32; 4]) -> String {
+    let [l, a, b] = OkLab::from_rgba_arr(col).lab();
+    if col[3] == 1.0 {
+        format!("oklab({l:.3} {a:.3} {b:.3})")
+    } else {
+        format!("oklab({l:.3} {a:.3} {b:.3} / {:.0}%)", col[3] * 100.0)
+    }
+}
+
+type Formatter = fn([f32; 4]) -> String;
+const COPY_FORMATS: [(&str, Formatter); 3] = [
+    ("Hex", ColSelection::format_rgba_alpha),
+    ("rgb()", css_rgb),
+    ("oklab()", css_oklab),
+];
+
+pub struct ColourPanel {
+    rect: Rect,
+    visible: bool,
+    editing: Option<(usize, String)>,
+    copied: Option<String>,
+}
+
+impl New for ColourPanel {
+    fn new(_handler: &mut GenHandler) -> Self {
+        Self {
+            rect: Rect::default(),
+            visible: false,
+            editing: None,
+            copied: None,
+        }
+    }
+}
+
+impl ColourPanel {
+    fn field_rect(&self, field: usize) -> Rect {
+        if field == ALPHA_FIELD {
+            Rect::new(self.rect.x + 60.0, self.rect.y + 136.0, 52.0, 26.0)
+        } else {
+            Rect::new(
+                self.rect.x + 60.0 + 52.0 * (field % 3) as f32,
+                self.rect.y + 34.0 * (field / 3) as f32 + 34.0,
+                50.0,
+                26.0
+            )
+        }
+    }
+
+    fn commit(&mut self, picker: &mut Picker) {
+        if let Some((field, text)) = self.editing.take() &&
+            let Ok(value) = text.parse::<f32>()
+        {
+            let col = picker.get_col_rgba().unwrap_or([0.0, 0.0, 0.0, 1.0]);
+            picker.set_col(Some(set_field(col, field, value)));
+        }
+    }
+}
+
+impl Node for ColourPanel {
+    fn update(&mut self, ctx: &mut AppContextHandler, node: &NodeStore) {
+        if ctx.user_inputs.instruction_pressed(ShortcutInstruction::ToggleColourPanel) {
+            self.visible = !self.visible;
+            self.editing = None;
+        }
+        if !self.visible {
+            self.rect = Rect::default();
+            return;
+        }
+
+        self.rect = Rect::new(screen_width() - 230.0, screen_height() - 330.0, 220.0, 240.0);
+        draw_rectangle(self.rect.x, self.rect.y, self.rect.w, self.rect.h, WHITE);
+        draw_rectangle_lines(self.rect.x, self.rect.y, self.rect.w, self.rect.h, 2.0, DARKGRAY);
+        draw_text("Colour", self.rect.x + 10.0, self.rect.y + 22.0, 18.0, BLACK);
+
+        if self.editing.is_some() && !ctx.user_inputs.last_touch_test(node) {
+            self.commit(ctx.store.get_mut::<Picker>());
+        }
+
+        let col = ctx.store.get_mut::<Picker>().get_col_rgba();
+        let values = col.map(field_values);
+
+        // FIELDS
+        for (index, (name, channels)) in SPACES.iter().enumerate() {
+            draw_text(name, self.rect.x + 10.0, self.field_rect(index * 3).y + 18.0, 16.0, BLACK);
+            for (channel, label) in channels.iter().enumerate() {
+                let field = index * 3 + channel;
+                let rect = self.field_rect(field);
+                draw_text(label, rect.x + 3.0, rect.y - 1.0, 12.0, DARKGRAY);
+            }
+        }
+        draw_text("Alpha", self.rect.x + 10.0, self.field_rect(ALPHA_FIELD).y + 18.0, 16.0, BLACK);
+
+        for field in 0..=ALPHA_FIELD {
+            let rect = self.field_rect(field);
+            let active = self.editing.as_ref().is_some_and(|(d, _)| *d == field);
+            let text = match (&self.editing, values) {
+                (Some((d, text)), _) if *d == field => format!("{text}|"),
+                (_, Some(values)) => format_value(field, values[field]),
+                (_, None) => "-".to_string(),
+            };
+
+            if sub_ui_button(
+                rect,
+                &text,
+                if active { ENABLEDCOL } else { DISABLEDCOL },
+                if active { ENABLEDCOL } else { DISABLEDHOVERCOL },
+                node,
+                ctx.user_inputs
+            ) && !active {
+                self.commit(ctx.store.get_mut::<Picker>());
+                self.editing = Some((field, String::new()));
+                while get_char_pressed().is_some() {}
+            }
+        }
+
+        // INPUT
+        if let Some((_, text)) = &mut self.editing {
+            ctx.user_inputs.disable_shortcuts();
+            while let Some(c) = get_char_pressed() {
+                if (c.is_ascii_digit() || c == '.' || c == '-') && text.len() < 8 {
+                    text.push(c);
+                }
+            }
+
+            if is_key_pressed(KeyCode::Backspace) {
+                text.pop();
+            }
+            if is_key_pressed(KeyCode::Escape) {
+                self.editing = None;
+            } else if is_key_pressed(KeyCode::Enter) {
+                self.commit(ctx.store.get_mut::<Picker>());
+            }
+        }
+
+        // COPY
+        let y = self.rect.y + 172.0;
+        for (index, (label, format)) in COPY_FORMATS.iter().enumerate() {
+            let rect = Rect::new(self.rect.x + 10.0 + 68.0 * index as f32, y, 64.0, 26.0);
+            match col {
+                Some(col) => if sub_ui_button(rect, label, DISABLEDCOL, DISABLEDHOVERCOL, node, ctx.user_inputs) {
+                    let text = format(col);
+                    clipboard_set(&text);
+                    self.copied = Some(text);
+                },
+                None => disabled_ui_button(rect, label, DISABLEDHOVERCOL),
+            }
+        }
+
+        if let Some(copied) = &self.copied {
+            draw_text(&format!("Copied {copied}"), self.rect.x + 10.0, y + 48.0, 14.0, DARKGRAY);
+        }
+    }
+
+    fn hit_detect(&mut self, pos: Vec2, node: &NodeStore, _store: &mut Store) -> Vec<WeakNode> {
+        if self.rect.contains(pos) {
+            vec![node.get_weak()]
+        } else {
+            vec![]
+        }
+    }
+}
@@ -14,6 +14,8 @@ pub struct DrawSection {
 impl New for DrawSection {
     fn new(handler: &mut GenHandler) -> Self {
         handler.push_data(PixelArray::default());
+        handler.push_data(ActiveSelection(None));
+        handler.push_data(History::default());
 
         Self {
             transform: Transform::new(screen_size()),
@@ -33,7 +35,37 @@ impl Node for DrawSection {
         let hover = ctx.user_inputs.hover_test(node);
 
         // ---------------- DRAW WORLD ----------------
-        ctx.store.get_mut::<PixelArray>().draw(&self.transform, self.grid_lines, self.crossboard);
+        let tile = *ctx.store.get::<TileMode>();
+        ctx.store.get_mut::<PixelArray>().wrap = tile.wrap();
+
+        let reference = shown_reference(&ctx.save_data.workspace, ctx.store);
+        let pixels = ctx.store.get::<PixelArray>();
+        if self.crossboard {
+            pixels.draw_crossboard(&self.transform);
+        }
+        if let Some((reference, image)) = reference && !reference.above {
+            image.draw(reference, &self.transform);
+        }
+        draw_onion_skin(ctx.store, &self.transform);
+        let filtered = ctx.store.get::<FilterPreview>().as_ref().map(|filter| {
+            let mut preview = pixels.clone();
+            preview.map_cols_in(ctx.store.get::<ActiveSelection>().map(|d| d.corners()), |col| filter.apply(col));
+            preview
+        });
+        let shown = filtered.as_ref().unwrap_or(pixels);
+        shown.draw_pixels(&self.transform, self.crossboard);
+        tile.draw(shown, &self.transform);
+        if let Some((reference, image)) = reference && reference.above {
+            image.draw(reference, &self.transform);
+        }
+        if self.grid_lines {
+            pixels.draw_grid_lines(&self.transform);
+        }
+        if let Some(selection) = **ctx.store.get::<ActiveSelection>() {
+            selection.draw(&self.transform);
+        }
+        let symmetry = *ctx.store.get::<Symmetry>();
+        symmetry.draw(&self.transform);
 
         let prev_touch = self.prev_touch;
         self.prev_touch = None;
@@ -88,21 +120,44 @@ impl Node for DrawSection {
                 }
             }
 
+            // An unlocked reference is being positioned, so dragging moves it instead of using the tool
+            let moving_reference = shown_reference(&ctx.save_data.workspace, ctx.store).is_some_and(|(reference, _)| !reference.locked);
+            if moving_reference && ctx.user_inputs.left_mouse_down && let Some(reference) = &mut ctx.save_data.workspace.reference {
+                let delta = ctx.user_inputs.mouse - ctx.user_inputs.prev_mouse;
+                reference.pos[0] += delta.x / self.transform.size();
+                reference.pos[1] += delta.y / self.transform.size();
+            }
+
+            // AXIS
+            let moving_axis = symmetry.mode != SymmetryMode::Off && (is_key_down(KeyCode::LeftAlt) || is_key_down(KeyCode::RightAlt));
+            if moving_axis && ctx.user_inputs.left_mouse_down {
+                // Snapped to the nearest pixel edge or centre
+                let world = self.transform.screen_to_world(&ScreenPos(ctx.user_inputs.mouse.x, ctx.user_inputs.mouse.y));
+                ctx.store.get_mut::<Symmetry>().axis = [(world.0 * 2.0).round() as i32, (world.1 * 2.0).round() as i32];
+            }
+            // Each stroke, fill, line or gradient is one undo step, saved as it starts
+            let editing = matches!(ctx.store.get::<DrawState>(), DrawState::Draw | DrawState::Fill | DrawState::Line | DrawState::Gradient);
+            if editing && !moving_reference && !moving_axis && ctx.user_inputs.left_mouse_pressed && hoverhold && hover {
+                History::record(ctx.store);
+            }
             let state = ctx.store.get::<DrawState>();
 
             // PAINT
             match state {
+                _ if moving_reference || moving_axis => {},
                 DrawState::Draw => {
                     if ctx.user_inputs.left_mouse_down && hoverhold && hover {
                         let world = self.transform.screen_to_world(&ScreenPos(ctx.user_inputs.mouse.x, ctx.user_inputs.mouse.y));
                         let col = ctx.store.get_mut::<Picker>().get_col_rgba();
                         if let Some(pos) = world.as_i16() {
+                            let pixels = ctx.store.get_mut::<PixelArray>();
                             if let Some(start) = prev_touch {
-                                ctx.store.get_mut::<PixelArray>().line(start, world.as_i16().unwrap(), col);
+                                for (start, end) in symmetry.images(start).into_iter().zip(symmetry.images(pos)) {
+                                    pixels.line(start, end, col);
+                                }
                             } else {
-                                match col {
-                                    Some(col) => ctx.store.get_mut::<PixelArray>().insert(Pixel { pos, col }),
-                                    None => ctx.store.get_mut::<PixelArray>().remove(pos),
+                                for pos in symmetry.images(pos) {
+                                    pixels.paint(pos, col);
                                 }
                             }
                             self.prev_touch = Some(pos);
@@ -110,17 +165,37 @@ impl Node for DrawSection {
                     }
                 },
                 DrawState::Picker => {
+                    let settings = *ctx.store.get::<EyedropperSettings>();
                     if ctx.user_inputs.left_let_go && hoverhold && hover {
-                        if let Some(&pixel) = ctx.store.get::<PixelArray>().get_at_mouse(ctx.user_inputs.mouse, &self.transform) {
-                            ctx.store.get_mut::<Picker>().set_col(Some(pixel.col));
+                        if let Some(pos) = self.transform.get_int_pos(ctx.user_inputs.mouse) {
+                            let pixels = ctx.store.get::<PixelArray>();
+                            let reference = shown_reference(&ctx.save_data.workspace, ctx.store);
+                            // Whichever is drawn on top gets sampled, falling back to the other where it's empty
+                            let sample = |pos| {
+                                let pixel = pixels.get_wrapped(pos).map(|pixel| pixel.col);
+                                match reference {
+                                    Some((reference, image)) if reference.above => image.sample(reference, pos).or(pixel),
+                                    Some((reference, image)) => pixel.or_else(|| image.sample(reference, pos)),
+                                    None => pixel,
+                                }
+                            };
+                            if let Some(col) = settings.sample(pos, sample) {
+                                ctx.store.get_mut::<Picker>().set_col(Some(col));
+                            }
                         }
                         ctx.store.overwrite(DrawState::Draw);
+                    } else if EyedropperSettings::averaging() && hover && let Some(pos) = self.transform.get_int_pos(ctx.user_inputs.mouse) {
+                        let radius = settings.size / 2;
+                        Selection::new([pos[0] - radius, pos[1] - radius], [pos[0] + radius, pos[1] + radius]).draw(&self.transform);
                     }
                 },
                 DrawState::Fill => {
                     if ctx.user_inputs.left_mouse_down && hoverhold && hover && let Some(pos) = self.transform.get_int_pos(ctx.user_inputs.mouse) {
                         let col = ctx.store.get_mut::<Picker>().get_col_rgba();
-                        ctx.store.get_mut::<PixelArray>().fill(pos, col);
+                        let pixels = ctx.store.get_mut::<PixelArray>();
+                        for pos in symmetry.images(pos) {
+                            pixels.fill(pos, col);
+                        }
                         ctx.store.overwrite(DrawState::Draw);
                     }
                 },
@@ -130,14 +205,71 @@ impl Node for DrawSection {
                     } else if ctx.user_inputs.left_let_go {
                         if hoverhold && hover && let Some(start) = self.line_start && let Some(end) = self.transform.get_int_pos(ctx.user_inputs.mouse) {
                             let col = ctx.store.get_mut::<Picker>().get_col_rgba();
-                            ctx.store.get_mut::<PixelArray>().line(start, end, col);
+                            let pixels = ctx.store.get_mut::<PixelArray>();
+                            for (start, end) in symmetry.images(start).into_iter().zip(symmetry.images(end)) {
+                                pixels.line(start, end, col);
+                            }
                         }
                         self.line_start = None
                     } else if ctx.user_inputs.left_mouse_down && hoverhold && hover && let Some(start) = self.line_start && let Some(end) = self.transform.get_int_pos(ctx.user_inputs.mouse) {
                         let mut pixels = PixelArray::default();
-                        pixels.line(start, end, if let Some(col) = ctx.store.get_mut::<Picker>().get_col_rgba() { Some(col) } else { Some([0.0, 0.0, 0.0, 0.6]) });
+                        pixels.wrap = tile.wrap();
+                        let col = if let Some(col) = ctx.store.get_mut::<Picker>().get_col_rgba() { Some(col) } else { Some([0.0, 0.0, 0.0, 0.6]) };
+                        for (start, end) in symmetry.images(start).into_iter().zip(symmetry.images(end)) {
+                            pixels.line(start, end, col);
+                        }
                         pixels.draw(&self.transform, false, false);
                     }
+                },
+                DrawState::Select => {
+                    if ctx.user_inputs.left_mouse_pressed && hoverhold && hover {
+                        self.line_start = self.transform.get_int_pos(ctx.user_inputs.mouse);
+                    } else if ctx.user_inputs.left_let_go {
+                        if hoverhold && hover && let Some(start) = self.line_start && let Some(end) = self.transform.get_int_pos(ctx.user_inputs.mouse) {
+                            // A click without dragging clears the selection
+                            ctx.store.overwrite(ActiveSelection((start != end).then(|| Selection::new(start, end))));
+                        }
+                        self.line_start = None
+                    } else if ctx.user_inputs.left_mouse_down && hoverhold && hover && let Some(start) = self.line_start && let Some(end) = self.transform.get_int_pos(ctx.user_inputs.mouse) {
+                        Selection::new(start, end).draw(&self.transform);
+                    }
+                },
+                DrawState::Gradient => {
+                    if ctx.user_inputs.left_mouse_pressed && hoverhold && hover {
+                        self.line_start = self.transform.get_int_pos(ctx.user_inputs.mouse);
+                    } else if (ctx.user_inputs.left_let_go || ctx.user_inputs.left_mouse_down) && let Some(start) = self.line_start {
+                        let end = self.transform.get_int_pos(ctx.user_inputs.mouse).filter(|_| hoverhold && hover);
+                        let region = match **ctx.store.get::<ActiveSelection>() {
+                            Some(selection) if selection.contains(start) => selection.positions().collect(),
+                            _ => ctx.store.get::<PixelArray>().fill_region(start),
+                        };
+                        let settings = *ctx.store.get::<GradientSettings>();
+
+                        if let Some(end) = end {
+                            let pixels = settings.pixels(region, start, end, &ctx.save_data.workspace.palette);
+                            if ctx.user_inputs.left_let_go {
+                                let array = ctx.store.get_mut::<PixelArray>();
+                                for pixel in pixels {
+                                    array.insert(pixel);
+                                }
+                            } else {
+                                let mut preview = PixelArray::default();
+                                for pixel in pixels {
+                                    preview.insert(pixel);
+                                }
+                                preview.draw(&self.transform, false, false);
+
+                                let half = self.transform.size() / 2.0;
+                                let start = self.transform.world_to_screen(&WorldPos(start[0] as f32, start[1] as f32));
+                                let end = self.transform.world_to_screen(&WorldPos(end[0] as f32, end[1] as f32));
+                                draw_line(start.0 + half, start.1 + half, end.0 + half, end.1 + half, 2.0, BLACK);
+                            }
+                        }
+
+                        if ctx.user_inputs.left_let_go {
+                            self.line_start = None
+                        }
+                    }
                 }
             }
         }
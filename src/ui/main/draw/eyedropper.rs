@@ -0,0 +1,85 @@
+use super::*;
+
+const SIZES: [i16; 4] = [3, 5, 7, 9];
+
+#[derive(Clone, Copy)]
+pub struct EyedropperSettings {
+    pub size: i16,
+    pub col_sel: ColSelection,
+}
+
+impl Default for EyedropperSettings {
+    fn default() -> Self {
+        Self {
+            size: 3,
+            col_sel: ColSelection::OkLab,
+        }
+    }
+}
+
+impl EyedropperSettings {
+    /// Whether the next pick averages an area instead of reading a single pixel
+    pub fn averaging() -> bool {
+        is_key_down(KeyCode::LeftShift) || is_key_down(KeyCode::RightShift)
+    }
+
+    /// Every position that gets sampled when picking at `center`
+    pub fn area(&self, center: [i16; 2]) -> impl Iterator<Item = [i16; 2]> {
+        let radius = if Self::averaging() { self.size / 2 } else { 0 };
+        (-radius..=radius).flat_map(move |y| (-radius..=radius).map(move |x| [center[0] + x, center[1] + y]))
+    }
+
+    /// Picks the colour at `center`, `get` returns `None` for empty positions which are left out of the average
+    pub fn sample(&self, center: [i16; 2], get: impl Fn([i16; 2]) -> Option<[f32; 4]>) -> Option<[f32; 4]> {
+        average_cols(self.area(center).filter_map(get), self.col_sel)
+    }
+}
+
+pub struct EyedropperOptions {
+    rect: Rect,
+}
+
+impl New for EyedropperOptions {
+    fn new(handler: &mut GenHandler) -> Self {
+        handler.push_data(EyedropperSettings::default());
+
+        Self {
+            rect: Rect::default(),
+        }
+    }
+}
+
+impl Node for EyedropperOptions {
+    fn update(&mut self, ctx: &mut AppContextHandler, node: &NodeStore) {
+        if *ctx.store.get::<DrawState>() != DrawState::Picker {
+            self.rect = Rect::default();
+            return;
+        }
+
+        self.rect = Rect::new(screen_width() - 230.0, 50.0, 220.0, 160.0);
+        let x = self.rect.x + 10.0;
+        draw_rectangle(self.rect.x, self.rect.y, self.rect.w, self.rect.h, WHITE);
+        draw_rectangle_lines(self.rect.x, self.rect.y, self.rect.w, self.rect.h, 2.0, DARKGRAY);
+        draw_text("Eyedropper", x, self.rect.y + 22.0, 18.0, BLACK);
+
+        let settings = ctx.store.get_mut::<EyedropperSettings>();
+
+        if sub_ui_button(Rect::new(x, 82.0, 200.0, 28.0), &format!("Area: {0}x{0}", settings.size), DISABLEDCOL, DISABLEDHOVERCOL, node, ctx.user_inputs) {
+            let index = SIZES.iter().position(|&d| d == settings.size).unwrap_or(0);
+            settings.size = SIZES[(index + 1) % SIZES.len()];
+        }
+        if sub_ui_button(Rect::new(x, 116.0, 200.0, 28.0), &format!("Average in: {}", settings.col_sel), DISABLEDCOL, DISABLEDHOVERCOL, node, ctx.user_inputs) {
+            settings.col_sel = settings.col_sel.toggle();
+        }
+
+        multiline_text(Rect::new(x, 160.0, 200.0, 50.0), "Hold shift while clicking to average the area. Works on the export preview too.", 14);
+    }
+
+    fn hit_detect(&mut self, pos: Vec2, node: &NodeStore, _store: &mut Store) -> Vec<WeakNode> {
+        if self.rect.contains(pos) {
+            vec![node.get_weak()]
+        } else {
+            vec![]
+        }
+    }
+}
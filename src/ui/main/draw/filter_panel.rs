@@ -0,0 +1,167 @@
+use super::*;
+use crate::colour::Filter;
+use crate::palette::quantise::{palette_of, Method};
+
+/// The filter to show on the drawing instead of the real colours, until it's applied
+#[tuple_deref]
+pub struct FilterPreview(pub Option<Filter>);
+
+pub struct FilterPanel {
+    rect: Rect,
+    visible: bool,
+    preview: bool,
+    kind: usize,
+    hue: f32,
+    saturation: f32,
+    brightness: f32,
+    from: [f32; 4],
+    to: [f32; 4],
+    tolerance: f32,
+    colours: usize,
+    /// What posterising reduces the drawing or selection to, picked again each frame while it's chosen
+    palette: Vec<[f32; 4]>,
+}
+
+impl New for FilterPanel {
+    fn new(handler: &mut GenHandler) -> Self {
+        handler.push_data(FilterPreview(None));
+
+        Self {
+            rect: Rect::default(),
+            visible: false,
+            preview: true,
+            kind: 0,
+            hue: 0.0,
+            saturation: 1.0,
+            brightness: 0.0,
+            from: [0.0, 0.0, 0.0, 1.0],
+            to: [1.0, 1.0, 1.0, 1.0],
+            tolerance: 0.05,
+            colours: 4,
+            palette: Vec::new(),
+        }
+    }
+}
+
+impl FilterPanel {
+    fn filters(&self) -> [Filter; 7] {
+        [
+            Filter::HueShift(self.hue / 360.0),
+            Filter::Saturation(self.saturation),
+            Filter::Brightness(self.brightness),
+            Filter::Invert,
+            Filter::Replace { from: self.from, to: self.to, tolerance: self.tolerance },
+            Filter::Posterise(self.palette.clone()),
+            Filter::Grayscale,
+        ]
+    }
+
+    /// The slider for whichever filter is chosen, if it has a setting
+    fn setting(&mut self, rect: Rect, ctx: &mut AppContextHandler, node: &NodeStore) -> bool {
+        let (label, value, min, range) = match self.kind {
+            0 => (format!("Hue: {}°", self.hue), self.hue, -180.0, 360.0),
+            1 => (format!("Saturation: {:.0}%", self.saturation * 100.0), self.saturation, 0.0, 2.0),
+            2 => (format!("Brightness: {:+.2}", self.brightness), self.brightness, -0.5, 1.0),
+            4 => (format!("Tolerance: {:.2}", self.tolerance), self.tolerance, 0.0, 0.5),
+            5 => (format!("Colours: {}", self.colours), self.colours as f32, 2.0, 30.0),
+            _ => {
+                draw_text("No settings", rect.x, rect.y + 8.0, 16.0, DARKGRAY);
+                return false;
+            }
+        };
+
+        let Some(value) = slider(ENABLEDCOL, DISABLEDCOL, rect, &label, value, min, range, ctx.user_inputs, node) else { return false };
+        match self.kind {
+            0 => self.hue = value.round(),
+            1 => self.saturation = (value * 100.0).round() / 100.0,
+            2 => self.brightness = (value * 100.0).round() / 100.0,
+            4 => self.tolerance = (value * 100.0).round() / 100.0,
+            _ => self.colours = value.round() as usize,
+        }
+        true
+    }
+}
+
+impl Node for FilterPanel {
+    fn update(&mut self, ctx: &mut AppContextHandler, node: &NodeStore) {
+        if ctx.user_inputs.instruction_pressed(ShortcutInstruction::ToggleFilterPanel) {
+            self.visible = !self.visible;
+        }
+        if !self.visible {
+            self.rect = Rect::default();
+            ctx.store.overwrite(FilterPreview(None));
+            return;
+        }
+
+        self.rect = ctx.store.get_mut::<PanelLayout>().place(176.0);
+        let x = self.rect.x + 10.0;
+        let y = self.rect.y;
+        draw_rectangle(self.rect.x, self.rect.y, self.rect.w, self.rect.h, WHITE);
+        draw_rectangle_lines(self.rect.x, self.rect.y, self.rect.w, self.rect.h, 2.0, DARKGRAY);
+        let scope = if ctx.store.get::<ActiveSelection>().is_some() { "selection" } else { "drawing" };
+        draw_text(&format!("Filter {scope}"), x, y + 22.0, 18.0, BLACK);
+
+        let mut changed = false;
+        let filters = self.filters();
+        if sub_ui_button(Rect::new(x, y + 30.0, 200.0, 26.0), filters[self.kind].name(), DISABLEDCOL, DISABLEDHOVERCOL, node, ctx.user_inputs) {
+            self.kind = (self.kind + 1) % filters.len();
+            changed = true;
+        }
+        changed |= self.setting(Rect::new(x, y + 82.0, 200.0, 12.0), ctx, node);
+
+        // The colours to swap come from the picker, the eraser being fully transparent
+        if matches!(self.filters()[self.kind], Filter::Replace { .. }) {
+            let col = ctx.store.get_mut::<Picker>().get_col_rgba().unwrap_or([0.0; 4]);
+            for (index, label) in ["Set A", "Set B"].into_iter().enumerate() {
+                let bx = x + 102.0 * index as f32;
+                if sub_ui_button(Rect::new(bx, y + 104.0, 64.0, 26.0), label, DISABLEDCOL, DISABLEDHOVERCOL, node, ctx.user_inputs) {
+                    if index == 0 {
+                        self.from = col;
+                    } else {
+                        self.to = col;
+                    }
+                    changed = true;
+                }
+                let swatch = [self.from, self.to][index];
+                draw_rectangle(bx + 68.0, y + 104.0, 26.0, 26.0, arr_to_macroquad(swatch));
+                draw_rectangle_lines(bx + 68.0, y + 104.0, 26.0, 26.0, 2.0, DARKGRAY);
+            }
+        }
+        if changed {
+            self.preview = true;
+        }
+
+        if sub_ui_button(
+            Rect::new(x, y + 140.0, 98.0, 26.0),
+            "Preview",
+            if self.preview { ENABLEDCOL } else { DISABLEDCOL },
+            if self.preview { ENABLEDHOVERCOL } else { DISABLEDHOVERCOL },
+            node,
+            ctx.user_inputs
+        ) {
+            self.preview = !self.preview;
+        }
+
+        let selection = ctx.store.get::<ActiveSelection>().map(|d| d.corners());
+        if self.kind == 5 {
+            self.palette = palette_of(ctx.store.get::<PixelArray>().cols_in(selection), self.colours, Method::MedianCut);
+        }
+        let filter = self.filters()[self.kind].clone();
+        if sub_ui_button(Rect::new(x + 102.0, y + 140.0, 98.0, 26.0), "Apply", ENABLEDCOL, ENABLEDHOVERCOL, node, ctx.user_inputs) {
+            History::record(ctx.store);
+            ctx.store.get_mut::<PixelArray>().map_cols_in(selection, |col| filter.apply(col));
+            // Otherwise the preview would show it applied a second time
+            self.preview = false;
+        }
+
+        ctx.store.overwrite(FilterPreview(self.preview.then_some(filter)));
+    }
+
+    fn hit_detect(&mut self, pos: Vec2, node: &NodeStore, _store: &mut Store) -> Vec<WeakNode> {
+        if self.rect.contains(pos) {
+            vec![node.get_weak()]
+        } else {
+            vec![]
+        }
+    }
+}
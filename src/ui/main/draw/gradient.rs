@@ -0,0 +1,217 @@
+use std::fmt::Display;
+
+use super::*;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GradientShape {
+    Linear,
+    Radial,
+    Angular,
+}
+
+impl Display for GradientShape {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Linear => write!(f, "Linear"),
+            Self::Radial => write!(f, "Radial"),
+            Self::Angular => write!(f, "Angular"),
+        }
+    }
+}
+
+impl GradientShape {
+    pub fn toggle(&self) -> Self {
+        match self {
+            Self::Linear => Self::Radial,
+            Self::Radial => Self::Angular,
+            Self::Angular => Self::Linear,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GradientFill {
+    Smooth,
+    Bayer2,
+    Bayer4,
+    Bayer8,
+    Checker,
+}
+
+impl Display for GradientFill {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Smooth => write!(f, "Smooth"),
+            Self::Bayer2 => write!(f, "Bayer 2x2"),
+            Self::Bayer4 => write!(f, "Bayer 4x4"),
+            Self::Bayer8 => write!(f, "Bayer 8x8"),
+            Self::Checker => write!(f, "Checker"),
+        }
+    }
+}
+
+impl GradientFill {
+    pub fn toggle(&self) -> Self {
+        match self {
+            Self::Smooth => Self::Bayer2,
+            Self::Bayer2 => Self::Bayer4,
+            Self::Bayer4 => Self::Bayer8,
+            Self::Bayer8 => Self::Checker,
+            Self::Checker => Self::Smooth,
+        }
+    }
+}
+
+/// Threshold in 0..1 for `pos` in a `2^bits` square ordered dither matrix
+fn bayer(pos: [i16; 2], bits: u32) -> f32 {
+    let size = 1 << bits;
+    let x = pos[0].rem_euclid(size) as u32;
+    let y = pos[1].rem_euclid(size) as u32;
+
+    let mut value = 0;
+    for bit in 0..bits {
+        let shift = 2 * (bits - 1 - bit);
+        value |= (((x ^ y) >> bit) & 1) << (shift + 1);
+        value |= ((y >> bit) & 1) << shift;
+    }
+
+    (value as f32 + 0.5) / (size * size) as f32
+}
+
+#[derive(Clone, Copy)]
+pub struct GradientSettings {
+    pub shape: GradientShape,
+    pub fill: GradientFill,
+    pub col_sel: ColSelection,
+    pub cols: [[f32; 4]; 2],
+}
+
+impl Default for GradientSettings {
+    fn default() -> Self {
+        Self {
+            shape: GradientShape::Linear,
+            fill: GradientFill::Smooth,
+            col_sel: ColSelection::OkLab,
+            cols: [[0.0, 0.0, 0.0, 1.0], [1.0, 1.0, 1.0, 1.0]],
+        }
+    }
+}
+
+impl GradientSettings {
+    /// How far along the gradient `pos` is, from 0 at `start` to 1 at `end`
+    fn frac(&self, pos: [i16; 2], start: [i16; 2], end: [i16; 2]) -> f32 {
+        let pos = vec2(pos[0] as f32, pos[1] as f32) - vec2(start[0] as f32, start[1] as f32);
+        let dir = vec2(end[0] as f32, end[1] as f32) - vec2(start[0] as f32, start[1] as f32);
+        if dir == Vec2::ZERO {
+            return 0.0;
+        }
+
+        match self.shape {
+            GradientShape::Linear => (pos.dot(dir) / dir.length_squared()).clamp(0.0, 1.0),
+            GradientShape::Radial => (pos.length() / dir.length()).clamp(0.0, 1.0),
+            GradientShape::Angular => ((pos.y.atan2(pos.x) - dir.y.atan2(dir.x)) / std::f32::consts::TAU).rem_euclid(1.0),
+        }
+    }
+
+    pub fn col_at(&self, pos: [i16; 2], start: [i16; 2], end: [i16; 2]) -> [f32; 4] {
+        let frac = self.frac(pos, start, end);
+        let [from, to] = self.cols;
+
+        let threshold = match self.fill {
+            GradientFill::Smooth => return self.col_sel.gradient(from, to, frac).map(|d| d.clamp(0.0, 1.0)),
+            GradientFill::Bayer2 => bayer(pos, 1),
+            GradientFill::Bayer4 => bayer(pos, 2),
+            GradientFill::Bayer8 => bayer(pos, 3),
+            GradientFill::Checker => {
+                if frac < 1.0 / 3.0 {
+                    1.0
+                } else if frac > 2.0 / 3.0 {
+                    0.0
+                } else {
+                    ((pos[0] as i32 + pos[1] as i32).rem_euclid(2)) as f32
+                }
+            }
+        };
+
+        if frac > threshold { to } else { from }
+    }
+
+    /// Colours every position in `region`, snapping them to the palette if it's locked
+    pub fn pixels(&self, region: impl IntoIterator<Item = [i16; 2]>, start: [i16; 2], end: [i16; 2], palette: &Palette) -> Vec<Pixel> {
+        region.into_iter().map(|pos| Pixel {
+            pos,
+            col: palette.restrict(self.col_at(pos, start, end), self.col_sel)
+        }).collect()
+    }
+}
+
+pub struct GradientOptions {
+    rect: Rect,
+}
+
+impl New for GradientOptions {
+    fn new(handler: &mut GenHandler) -> Self {
+        handler.push_data(GradientSettings::default());
+
+        Self {
+            rect: Rect::default(),
+        }
+    }
+}
+
+impl Node for GradientOptions {
+    fn update(&mut self, ctx: &mut AppContextHandler, node: &NodeStore) {
+        if *ctx.store.get::<DrawState>() != DrawState::Gradient {
+            self.rect = Rect::default();
+            return;
+        }
+
+        self.rect = Rect::new(screen_width() - 230.0, 50.0, 220.0, 262.0);
+        let x = self.rect.x + 10.0;
+        draw_rectangle(self.rect.x, self.rect.y, self.rect.w, self.rect.h, WHITE);
+        draw_rectangle_lines(self.rect.x, self.rect.y, self.rect.w, self.rect.h, 2.0, DARKGRAY);
+        draw_text("Gradient", x, self.rect.y + 22.0, 18.0, BLACK);
+
+        let picker_col = ctx.store.get_mut::<Picker>().get_col_rgba();
+        let settings = ctx.store.get_mut::<GradientSettings>();
+
+        if sub_ui_button(Rect::new(x, 82.0, 200.0, 28.0), &format!("Shape: {}", settings.shape), DISABLEDCOL, DISABLEDHOVERCOL, node, ctx.user_inputs) {
+            settings.shape = settings.shape.toggle();
+        }
+        if sub_ui_button(Rect::new(x, 116.0, 200.0, 28.0), &format!("Fill: {}", settings.fill), DISABLEDCOL, DISABLEDHOVERCOL, node, ctx.user_inputs) {
+            settings.fill = settings.fill.toggle();
+        }
+        if settings.fill == GradientFill::Smooth {
+            if sub_ui_button(Rect::new(x, 150.0, 200.0, 28.0), &format!("Interpolate in: {}", settings.col_sel), DISABLEDCOL, DISABLEDHOVERCOL, node, ctx.user_inputs) {
+                settings.col_sel = settings.col_sel.toggle();
+            }
+        } else {
+            disabled_ui_button(Rect::new(x, 150.0, 200.0, 28.0), &format!("Interpolate in: {}", settings.col_sel), DISABLEDHOVERCOL);
+        }
+
+        // COLOURS
+        for (index, label) in ["Start", "End"].iter().enumerate() {
+            let rect = Rect::new(x + 60.0 * index as f32, 186.0, 28.0, 28.0);
+            let hovered = ctx.user_inputs.hover_test(node) && rect.contains(ctx.user_inputs.mouse);
+            let let_go = ctx.user_inputs.left_let_go && ctx.user_inputs.last_touch_test(node) && rect.contains(ctx.user_inputs.lasttouch_mouse);
+            if col_button(rect, hovered, let_go, DISABLEDCOL, DISABLEDHOVERCOL) && let Some(col) = picker_col {
+                settings.cols[index] = col;
+            }
+            draw_rectangle(rect.x + 4.0, rect.y + 4.0, 20.0, 20.0, arr_to_macroquad(settings.cols[index]));
+            draw_text(label, rect.x, rect.y + 44.0, 16.0, BLACK);
+        }
+        if sub_ui_button(Rect::new(x + 120.0, 186.0, 80.0, 28.0), "Swap", DISABLEDCOL, DISABLEDHOVERCOL, node, ctx.user_inputs) {
+            settings.cols.swap(0, 1);
+        }
+
+        multiline_text(Rect::new(x, 244.0, 200.0, 60.0), "Click a colour to set it from the picker, then drag over the selection or an area.", 14);
+    }
+
+    fn hit_detect(&mut self, pos: Vec2, node: &NodeStore, _store: &mut Store) -> Vec<WeakNode> {
+        if self.rect.contains(pos) {
+            vec![node.get_weak()]
+        } else {
+            vec![]
+        }
+    }
+}
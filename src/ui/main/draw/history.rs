@@ -0,0 +1,51 @@
+use super::*;
+use crate::animation::Animation;
+
+const MAX_STEPS: usize = 100;
+
+/// Copies of a frame from before each edit, with the index of the frame they came from
+#[derive(Default)]
+pub struct History {
+    undo: Vec<(usize, PixelArray)>,
+    redo: Vec<(usize, PixelArray)>,
+}
+
+impl History {
+    /// Saves the current frame before it gets edited
+    pub fn record(store: &mut Store) {
+        let frame = store.get::<Animation>().current();
+        let pixels = store.get::<PixelArray>().clone();
+        let history = store.get_mut::<History>();
+
+        history.undo.push((frame, pixels));
+        if history.undo.len() > MAX_STEPS {
+            history.undo.remove(0);
+        }
+        history.redo.clear();
+    }
+
+    /// Frame indices change when frames are added or removed, so the steps no longer point at the right ones
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+    }
+
+    /// Goes back one step, or forward one if `redo`, switching to the frame it was made on
+    pub fn step(store: &mut Store, redo: bool) {
+        let history = store.get_mut::<History>();
+        let Some((frame, mut pixels)) = (if redo { history.redo.pop() } else { history.undo.pop() }) else { return };
+
+        let mut active = std::mem::take(store.get_mut::<PixelArray>());
+        store.get_mut::<Animation>().select(frame, &mut active);
+        std::mem::swap(&mut active, &mut pixels);
+        active.wrap = pixels.wrap;
+        store.overwrite(active);
+
+        let history = store.get_mut::<History>();
+        if redo {
+            history.undo.push((frame, pixels));
+        } else {
+            history.redo.push((frame, pixels));
+        }
+    }
+}
@@ -0,0 +1,162 @@
+use std::path::PathBuf;
+
+use image::{RgbaImage, imageops::{self, FilterType}};
+use rfd::FileDialog;
+
+use super::*;
+use crate::palette::quantise::{self, Dither, Method};
+
+/// Turns a picture into pixel art: shrinks it, picks a palette for it and dithers it into the current frame
+pub struct ImportPanel {
+    rect: Rect,
+    visible: bool,
+    source: Option<(PathBuf, RgbaImage)>,
+    width: u32,
+    colours: usize,
+    method: usize,
+    dither: usize,
+    message: Option<String>,
+}
+
+impl New for ImportPanel {
+    fn new(_handler: &mut GenHandler) -> Self {
+        Self {
+            rect: Rect::default(),
+            visible: false,
+            source: None,
+            width: 64,
+            colours: 16,
+            method: 1,
+            dither: 0,
+            message: None,
+        }
+    }
+}
+
+impl ImportPanel {
+    fn import(&mut self, ctx: &mut AppContextHandler) {
+        let Some((_, image)) = &self.source else { return };
+
+        // Never scaled up, so small sprites come in as they are
+        let width = self.width.min(image.width()).max(1);
+        let height = ((image.height() as f32 * width as f32 / image.width() as f32).round() as u32).max(1);
+        let small = imageops::resize(image, width, height, FilterType::Triangle);
+
+        // A locked palette is kept, and the image is fitted to it instead.
+        // Otherwise the new colours go after the saved ones, since undo only covers the pixels.
+        let palette = &mut ctx.save_data.workspace.palette;
+        let cols = if palette.locked && !palette.is_empty() {
+            palette.cols().collect::<Vec<_>>()
+        } else {
+            let cols = quantise::palette(&small, self.colours, Method::ALL[self.method]);
+            let new = cols.iter().copied().filter(|&col| !palette.contains(col)).collect::<Vec<_>>();
+            palette.append(new);
+            cols
+        };
+        let quantised = quantise::remap(&small, &cols, Dither::ALL[self.dither]);
+
+        History::record(ctx.store);
+        let pixels = ctx.store.get_mut::<PixelArray>();
+        pixels.clear();
+        for (index, col) in quantised.cols.into_iter().enumerate() {
+            let Some(col) = col else { continue };
+            let (x, y) = (index as u32 % quantised.width, index as u32 / quantised.width);
+            pixels.insert(Pixel { pos: [x as i16, y as i16], col });
+        }
+        self.message = Some(format!("Imported at {width} x {height} with {} colours.", cols.len()));
+    }
+}
+
+impl Node for ImportPanel {
+    fn update(&mut self, ctx: &mut AppContextHandler, node: &NodeStore) {
+        if ctx.user_inputs.instruction_pressed(ShortcutInstruction::ToggleImportPanel) {
+            self.visible = !self.visible;
+        }
+        if !self.visible {
+            self.rect = Rect::default();
+            return;
+        }
+
+        self.rect = ctx.store.get_mut::<PanelLayout>().place(250.0);
+        let x = self.rect.x + 10.0;
+        let y = self.rect.y;
+        draw_rectangle(self.rect.x, self.rect.y, self.rect.w, self.rect.h, WHITE);
+        draw_rectangle_lines(self.rect.x, self.rect.y, self.rect.w, self.rect.h, 2.0, DARKGRAY);
+        draw_text("Import image", x, y + 22.0, 18.0, BLACK);
+
+        if sub_ui_button(Rect::new(x, y + 30.0, 200.0, 28.0), "Choose image", DISABLEDCOL, DISABLEDHOVERCOL, node, ctx.user_inputs) &&
+            let Some(path) = FileDialog::new().set_title("Import image").add_filter("Image", IMAGE_EXTENSIONS).pick_file()
+        {
+            match image::open(&path) {
+                Ok(image) => {
+                    self.message = None;
+                    self.source = Some((path, image.to_rgba8()));
+                },
+                Err(err) => self.message = Some(format!("Couldn't open `{}`: {err}", path.display())),
+            }
+        }
+        let mut name = match &self.source {
+            Some((path, image)) => format!("{} ({} x {})", path.file_name().unwrap_or_default().to_string_lossy(), image.width(), image.height()),
+            None => "No image chosen".to_string(),
+        };
+        cut_text(&mut name, 200.0);
+        draw_text(&name, x, y + 76.0, 14.0, DARKGRAY);
+
+        if let Some(value) = slider(
+            ENABLEDCOL,
+            DISABLEDCOL,
+            Rect::new(x, y + 106.0, 200.0, 12.0),
+            &format!("Width: {} px", self.width),
+            self.width as f32,
+            8.0,
+            248.0,
+            ctx.user_inputs,
+            node
+        ) {
+            self.width = value.round() as u32;
+        }
+
+        if ctx.save_data.workspace.palette.locked && !ctx.save_data.workspace.palette.is_empty() {
+            draw_text("Using the locked palette", x, y + 146.0, 16.0, DARKGRAY);
+        } else if let Some(value) = slider(
+            ENABLEDCOL,
+            DISABLEDCOL,
+            Rect::new(x, y + 146.0, 200.0, 12.0),
+            &format!("Colours: {}", self.colours),
+            self.colours as f32,
+            2.0,
+            30.0,
+            ctx.user_inputs,
+            node
+        ) {
+            self.colours = value.round() as usize;
+        }
+
+        if sub_ui_button(Rect::new(x, y + 168.0, 98.0, 26.0), Method::ALL[self.method].name(), DISABLEDCOL, DISABLEDHOVERCOL, node, ctx.user_inputs) {
+            self.method = (self.method + 1) % Method::ALL.len();
+        }
+        if sub_ui_button(Rect::new(x + 102.0, y + 168.0, 98.0, 26.0), Dither::ALL[self.dither].name(), DISABLEDCOL, DISABLEDHOVERCOL, node, ctx.user_inputs) {
+            self.dither = (self.dither + 1) % Dither::ALL.len();
+        }
+
+        if self.source.is_none() {
+            disabled_ui_button(Rect::new(x, y + 200.0, 200.0, 28.0), "Import", DISABLEDCOL);
+        } else if sub_ui_button(Rect::new(x, y + 200.0, 200.0, 28.0), "Import", ENABLEDCOL, ENABLEDHOVERCOL, node, ctx.user_inputs) {
+            self.import(ctx);
+        }
+
+        if let Some(message) = &self.message {
+            let mut message = message.clone();
+            cut_text(&mut message, 200.0);
+            draw_text(&message, x, y + 244.0, 14.0, DARKGRAY);
+        }
+    }
+
+    fn hit_detect(&mut self, pos: Vec2, node: &NodeStore, _store: &mut Store) -> Vec<WeakNode> {
+        if self.rect.contains(pos) {
+            vec![node.get_weak()]
+        } else {
+            vec![]
+        }
+    }
+}
@@ -1,17 +1,59 @@
 use colour::ColSelection;
+use colour_picker::{ColPicker, Picker};
 
 use super::*;
 mod sidebar;
 mod draw_section;
+mod colour_panel;
+mod gradient;
+mod eyedropper;
+mod reference;
+mod symmetry;
+mod timeline;
+mod tile;
+mod selection;
+mod history;
+mod transform_panel;
+mod filter_panel;
+mod outline_panel;
+mod import_panel;
+mod panel_layout;
 use sidebar::Sidebar;
 pub use sidebar::DrawState;
 use draw_section::DrawSection;
+use colour_panel::ColourPanel;
+use gradient::{GradientOptions, GradientSettings};
+use eyedropper::EyedropperOptions;
+pub use eyedropper::EyedropperSettings;
+use reference::{ReferencePanel, shown_reference};
+use symmetry::{Symmetry, SymmetryMode, SymmetryPanel};
+use timeline::{Timeline, draw_onion_skin};
+use tile::{TileMode, TilePanel};
+pub use selection::{ActiveSelection, Selection};
+use history::History;
+use transform_panel::TransformPanel;
+use filter_panel::{FilterPanel, FilterPreview};
+use outline_panel::OutlinePanel;
+use import_panel::ImportPanel;
+use panel_layout::PanelLayout;
 
 pub struct Draw;
 
 impl New for Draw {
-    fn new(handler: &mut GenHandler) -> Self { // 0 is Sidebar, 1 is DrawSection
+    fn new(handler: &mut GenHandler) -> Self { // 0 is Sidebar, 1 is ColourPanel, 2 is GradientOptions, 3 is EyedropperOptions, 4 is ReferencePanel, 5 is SymmetryPanel, 6 is TilePanel, 7 is TransformPanel, 8 is FilterPanel, 9 is OutlinePanel, 10 is ImportPanel, 11 is Timeline, 12 is DrawSection
+        handler.push_data(PanelLayout::default());
         handler.push_child::<Sidebar>();
+        handler.push_child::<ColourPanel>();
+        handler.push_child::<GradientOptions>();
+        handler.push_child::<EyedropperOptions>();
+        handler.push_child::<ReferencePanel>();
+        handler.push_child::<SymmetryPanel>();
+        handler.push_child::<TilePanel>();
+        handler.push_child::<TransformPanel>();
+        handler.push_child::<FilterPanel>();
+        handler.push_child::<OutlinePanel>();
+        handler.push_child::<ImportPanel>();
+        handler.push_child::<Timeline>();
         handler.push_child::<DrawSection>();
         
         Self
@@ -21,6 +63,18 @@ impl New for Draw {
 impl Node for Draw {
     fn update(&mut self, ctx: &mut AppContextHandler, node: &NodeStore) {
         clear_background(WHITE);
+
+        // Done before any children so a colour set last frame is snapped before it can be drawn with
+        let picker = ctx.store.get_mut::<Picker>();
+        if let Some(col) = picker.get_col_rgba() {
+            let col_sel = picker.get_coltype();
+            let restricted = ctx.save_data.workspace.palette.restrict(col, col_sel);
+            if restricted != col {
+                ctx.store.get_mut::<Picker>().set_col(Some(restricted));
+            }
+        }
+
+        ctx.store.overwrite(PanelLayout::default());
         for child in node.get_children().iter().rev() {
             child.update(ctx);
         }
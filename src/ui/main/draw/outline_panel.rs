@@ -0,0 +1,103 @@
+use super::*;
+
+pub struct OutlinePanel {
+    rect: Rect,
+    visible: bool,
+    col: [f32; 4],
+    diagonal: bool,
+    inside: bool,
+    offset: [i16; 2],
+}
+
+impl New for OutlinePanel {
+    fn new(_handler: &mut GenHandler) -> Self {
+        Self {
+            rect: Rect::default(),
+            visible: false,
+            col: [0.0, 0.0, 0.0, 1.0],
+            diagonal: false,
+            inside: false,
+            offset: [1, 1],
+        }
+    }
+}
+
+impl OutlinePanel {
+    /// Runs `f` over the selection, or the whole drawing without one, as a single undo step
+    fn apply(ctx: &mut AppContextHandler, f: impl FnOnce(&mut PixelArray, Option<[[i16; 2]; 2]>)) {
+        History::record(ctx.store);
+        let selection = ctx.store.get::<ActiveSelection>().map(|d| d.corners());
+        f(ctx.store.get_mut::<PixelArray>(), selection);
+    }
+}
+
+impl Node for OutlinePanel {
+    fn update(&mut self, ctx: &mut AppContextHandler, node: &NodeStore) {
+        if ctx.user_inputs.instruction_pressed(ShortcutInstruction::ToggleOutlinePanel) {
+            self.visible = !self.visible;
+        }
+        if !self.visible {
+            self.rect = Rect::default();
+            return;
+        }
+
+        self.rect = ctx.store.get_mut::<PanelLayout>().place(150.0);
+        let x = self.rect.x + 10.0;
+        let y = self.rect.y;
+        let column = |index: f32| x + 68.0 * index;
+        draw_rectangle(self.rect.x, self.rect.y, self.rect.w, self.rect.h, WHITE);
+        draw_rectangle_lines(self.rect.x, self.rect.y, self.rect.w, self.rect.h, 2.0, DARKGRAY);
+        let scope = if ctx.store.get::<ActiveSelection>().is_some() { "selection" } else { "drawing" };
+        draw_text(&format!("Outline {scope}"), x, y + 22.0, 18.0, BLACK);
+
+        // COLOUR
+        if sub_ui_button(Rect::new(x, y + 30.0, 166.0, 26.0), "Use picker colour", DISABLEDCOL, DISABLEDHOVERCOL, node, ctx.user_inputs)
+            && let Some(col) = ctx.store.get_mut::<Picker>().get_col_rgba()
+        {
+            self.col = col;
+        }
+        draw_rectangle(x + 174.0, y + 30.0, 26.0, 26.0, arr_to_macroquad(self.col));
+        draw_rectangle_lines(x + 174.0, y + 30.0, 26.0, 26.0, 2.0, DARKGRAY);
+
+        // OUTLINE
+        if sub_ui_button(Rect::new(column(0.0), y + 62.0, 64.0, 26.0), if self.diagonal { "8-way" } else { "4-way" }, DISABLEDCOL, DISABLEDHOVERCOL, node, ctx.user_inputs) {
+            self.diagonal = !self.diagonal;
+        }
+        if sub_ui_button(Rect::new(column(1.0), y + 62.0, 64.0, 26.0), if self.inside { "Inside" } else { "Outside" }, DISABLEDCOL, DISABLEDHOVERCOL, node, ctx.user_inputs) {
+            self.inside = !self.inside;
+        }
+        if sub_ui_button(Rect::new(column(2.0), y + 62.0, 64.0, 26.0), "Outline", ENABLEDCOL, ENABLEDHOVERCOL, node, ctx.user_inputs) {
+            let (col, diagonal, inside) = (self.col, self.diagonal, self.inside);
+            Self::apply(ctx, |pixels, region| pixels.outline(col, diagonal, inside, region));
+        }
+
+        // SHADOW
+        for (axis, label) in ["X", "Y"].iter().enumerate() {
+            if let Some(value) = slider(
+                ENABLEDCOL,
+                DISABLEDCOL,
+                Rect::new(column(axis as f32), y + 120.0, 64.0, 12.0),
+                &format!("{label}: {}", self.offset[axis]),
+                self.offset[axis] as f32,
+                -8.0,
+                16.0,
+                ctx.user_inputs,
+                node
+            ) {
+                self.offset[axis] = value.round() as i16;
+            }
+        }
+        if sub_ui_button(Rect::new(column(2.0), y + 110.0, 64.0, 26.0), "Shadow", ENABLEDCOL, ENABLEDHOVERCOL, node, ctx.user_inputs) {
+            let (col, offset) = (self.col, self.offset);
+            Self::apply(ctx, |pixels, region| pixels.drop_shadow(col, offset, region));
+        }
+    }
+
+    fn hit_detect(&mut self, pos: Vec2, node: &NodeStore, _store: &mut Store) -> Vec<WeakNode> {
+        if self.rect.contains(pos) {
+            vec![node.get_weak()]
+        } else {
+            vec![]
+        }
+    }
+}
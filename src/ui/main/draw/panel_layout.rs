@@ -0,0 +1,36 @@
+use super::*;
+
+const LEFT: f32 = 163.0;
+const TOP: f32 = 50.0;
+const WIDTH: f32 = 220.0;
+const GAP: f32 = 10.0;
+
+/// Where the next open floating panel goes this frame.
+/// Panels are stacked down columns from the left, stopping above the timeline, so they don't cover each other while there's room.
+pub struct PanelLayout {
+    column: usize,
+    y: f32,
+}
+
+impl Default for PanelLayout {
+    fn default() -> Self {
+        Self { column: 0, y: TOP }
+    }
+}
+
+impl PanelLayout {
+    /// Takes the next free spot for a panel `h` tall
+    pub fn place(&mut self, h: f32) -> Rect {
+        let bottom = screen_height() - 90.0;
+        if self.y + h > bottom && self.y > TOP {
+            self.column += 1;
+            self.y = TOP;
+        }
+
+        // Once every column is full they're reused from the first, since there's nowhere else to go
+        let columns = (((screen_width() - LEFT) / (WIDTH + GAP)) as usize).max(1);
+        let rect = Rect::new(LEFT + (WIDTH + GAP) * (self.column % columns) as f32, self.y, WIDTH, h);
+        self.y += h + GAP;
+        rect
+    }
+}
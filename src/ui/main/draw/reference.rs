@@ -0,0 +1,164 @@
+use std::path::PathBuf;
+
+use rfd::FileDialog;
+
+use super::*;
+use crate::reference::{Reference, ReferenceImage};
+
+/// The pixels of the workspace's reference, loaded when its path changes
+#[tuple_deref]
+pub struct LoadedReference(pub Option<ReferenceImage>);
+
+/// The reference and its pixels, but only if it's being shown
+pub fn shown_reference<'a>(workspace: &'a WorkSpace, store: &'a Store) -> Option<(&'a Reference, &'a ReferenceImage)> {
+    match (&workspace.reference, &**store.get::<LoadedReference>()) {
+        (Some(reference), Some(image)) if reference.visible && image.path == reference.path => Some((reference, image)),
+        _ => None
+    }
+}
+
+pub struct ReferencePanel {
+    rect: Rect,
+    visible: bool,
+    /// The path that couldn't be loaded and why, so it isn't retried every frame
+    failed: Option<(PathBuf, String)>,
+}
+
+impl New for ReferencePanel {
+    fn new(handler: &mut GenHandler) -> Self {
+        handler.push_data(LoadedReference(None));
+
+        Self {
+            rect: Rect::default(),
+            visible: false,
+            failed: None,
+        }
+    }
+}
+
+impl ReferencePanel {
+    /// Loads the reference's pixels if they aren't already.
+    /// If they can't be the reference is still kept, so it isn't lost from the workspace, and the panel is opened to show why.
+    fn sync(&mut self, ctx: &mut AppContextHandler) {
+        let loaded = ctx.store.get_mut::<LoadedReference>();
+        match &ctx.save_data.workspace.reference {
+            Some(reference) if self.failed.as_ref().is_some_and(|(path, _)| *path == reference.path) => **loaded = None,
+            Some(reference) if loaded.as_ref().is_none_or(|image| image.path != reference.path) => {
+                match ReferenceImage::load(&reference.path) {
+                    Ok(image) => **loaded = Some(image),
+                    Err(err) => {
+                        **loaded = None;
+                        self.failed = Some((reference.path.clone(), err));
+                        self.visible = true;
+                    }
+                }
+            },
+            None => **loaded = None,
+            _ => {}
+        }
+    }
+}
+
+impl Node for ReferencePanel {
+    fn update(&mut self, ctx: &mut AppContextHandler, node: &NodeStore) {
+        self.sync(ctx);
+
+        if ctx.user_inputs.instruction_pressed(ShortcutInstruction::ToggleReference) {
+            self.visible = !self.visible;
+        }
+        if !self.visible {
+            self.rect = Rect::default();
+            return;
+        }
+
+        self.rect = ctx.store.get_mut::<PanelLayout>().place(280.0);
+        let x = self.rect.x + 10.0;
+        let y = self.rect.y;
+        draw_rectangle(self.rect.x, self.rect.y, self.rect.w, self.rect.h, WHITE);
+        draw_rectangle_lines(self.rect.x, self.rect.y, self.rect.w, self.rect.h, 2.0, DARKGRAY);
+        draw_text("Reference image", x, y + 22.0, 18.0, BLACK);
+
+        if sub_ui_button(Rect::new(x, y + 34.0, 150.0, 28.0), "Load image", ENABLEDCOL, ENABLEDHOVERCOL, node, ctx.user_inputs) &&
+            let Some(path) = FileDialog::new().set_title("Load reference image").add_filter("Image", IMAGE_EXTENSIONS).pick_file()
+        {
+            self.failed = None;
+            ctx.save_data.workspace.reference = Some(Reference::new(path));
+        }
+
+        let Some(reference) = &mut ctx.save_data.workspace.reference else {
+            disabled_ui_button(Rect::new(x + 154.0, y + 34.0, 46.0, 28.0), "X", DISABLEDCOL);
+            return;
+        };
+
+        if sub_ui_button(Rect::new(x + 154.0, y + 34.0, 46.0, 28.0), "X", DISABLEDCOL, DISABLEDHOVERCOL, node, ctx.user_inputs) {
+            ctx.save_data.workspace.reference = None;
+            self.failed = None;
+            return;
+        }
+
+        if let Some((path, err)) = &self.failed && *path == reference.path {
+            if sub_ui_button(Rect::new(x, y + 68.0, 200.0, 28.0), "Retry loading", DISABLEDCOL, DISABLEDHOVERCOL, node, ctx.user_inputs) {
+                self.failed = None;
+            } else {
+                multiline_text(Rect::new(x, y + 106.0, 200.0, 170.0), err, 14);
+            }
+            return;
+        }
+
+        if sub_ui_button(Rect::new(x, y + 68.0, 98.0, 28.0), if reference.visible { "Hide" } else { "Show" }, DISABLEDCOL, DISABLEDHOVERCOL, node, ctx.user_inputs) {
+            reference.visible = !reference.visible;
+        }
+        if sub_ui_button(Rect::new(x + 102.0, y + 68.0, 98.0, 28.0), if reference.locked { "Unlock" } else { "Lock" }, DISABLEDCOL, DISABLEDHOVERCOL, node, ctx.user_inputs) {
+            reference.locked = !reference.locked;
+        }
+        if sub_ui_button(Rect::new(x, y + 102.0, 200.0, 28.0), if reference.above { "Drawn above pixels" } else { "Drawn beneath pixels" }, DISABLEDCOL, DISABLEDHOVERCOL, node, ctx.user_inputs) {
+            reference.above = !reference.above;
+        }
+
+        if let Some(value) = slider(
+            ENABLEDCOL,
+            DISABLEDCOL,
+            Rect::new(x, y + 160.0, 200.0, 18.0),
+            &format!("Opacity: {}%", (reference.opacity * 100.0).round()),
+            reference.opacity,
+            0.0,
+            1.0,
+            ctx.user_inputs,
+            node
+        ) {
+            reference.opacity = value;
+        }
+
+        // Scale goes up in powers of two, so small and large images both get a usable range
+        if !reference.locked && let Some(value) = slider(
+            ENABLEDCOL,
+            DISABLEDCOL,
+            Rect::new(x, y + 210.0, 200.0, 18.0),
+            &format!("Scale: {:.3}", reference.scale),
+            reference.scale.log2(),
+            -6.0,
+            8.0,
+            ctx.user_inputs,
+            node
+        ) {
+            reference.scale = value.exp2();
+        } else if reference.locked {
+            draw_text(&format!("Scale: {:.3}", reference.scale), x, y + 200.0, 18.0, BLACK);
+        }
+
+        let hint = if reference.locked {
+            "Locked. Unlock to move or scale it."
+        } else {
+            "Drag on the canvas to move it, then lock it to draw."
+        };
+        multiline_text(Rect::new(x, y + 240.0, 200.0, 40.0), hint, 14);
+    }
+
+    fn hit_detect(&mut self, pos: Vec2, node: &NodeStore, _store: &mut Store) -> Vec<WeakNode> {
+        if self.rect.contains(pos) {
+            vec![node.get_weak()]
+        } else {
+            vec![]
+        }
+    }
+}
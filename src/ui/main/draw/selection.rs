@@ -0,0 +1,44 @@
+use super::*;
+
+/// A rectangle of pixels, both corners inclusive
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Selection {
+    min: [i16; 2],
+    max: [i16; 2],
+}
+
+#[tuple_deref]
+pub struct ActiveSelection(pub Option<Selection>);
+
+impl Selection {
+    pub fn new(a: [i16; 2], b: [i16; 2]) -> Self {
+        Self {
+            min: [a[0].min(b[0]), a[1].min(b[1])],
+            max: [a[0].max(b[0]), a[1].max(b[1])],
+        }
+    }
+
+    pub fn contains(&self, pos: [i16; 2]) -> bool {
+        (self.min[0]..=self.max[0]).contains(&pos[0]) &&
+        (self.min[1]..=self.max[1]).contains(&pos[1])
+    }
+
+    pub fn corners(&self) -> [[i16; 2]; 2] {
+        [self.min, self.max]
+    }
+
+    pub fn positions(&self) -> impl Iterator<Item = [i16; 2]> {
+        let [minx, miny] = self.min;
+        let [maxx, maxy] = self.max;
+        (miny..=maxy).flat_map(move |y| (minx..=maxx).map(move |x| [x, y]))
+    }
+
+    pub fn draw(&self, transform: &Transform) {
+        let start = transform.world_to_screen(&WorldPos(self.min[0] as f32, self.min[1] as f32));
+        let w = (self.max[0] as f32 - self.min[0] as f32 + 1.0) * transform.size();
+        let h = (self.max[1] as f32 - self.min[1] as f32 + 1.0) * transform.size();
+
+        draw_rectangle_lines(start.0, start.1, w, h, 3.0, BLACK);
+        draw_rectangle_lines(start.0 + 1.0, start.1 + 1.0, w - 2.0, h - 2.0, 1.0, WHITE);
+    }
+}
@@ -10,6 +10,8 @@ pub enum DrawState {
     Fill,
     Picker,
     Draw,
+    Select,
+    Gradient,
 }
 
 impl Display for DrawState {
@@ -19,6 +21,8 @@ impl Display for DrawState {
             Self::Fill => write!(f, "Fill"),
             Self::Picker => write!(f, "Picker"),
             Self::Draw => write!(f, "Draw"),
+            Self::Select => write!(f, "Select"),
+            Self::Gradient => write!(f, "Gradient"),
         }
     }
 }
@@ -31,6 +35,8 @@ impl FromStr for DrawState {
             "Fill" => Self::Fill,
             "Picker" => Self::Picker,
             "Draw" => Self::Draw,
+            "Select" => Self::Select,
+            "Gradient" => Self::Gradient,
             _ => return Err(())
         };
         Ok(result)
@@ -40,14 +40,11 @@ impl Node for HexInput {
         // input
         if self.active {
             ctx.user_inputs.disable_shortcuts();
+            let mut changed = false;
             while let Some(c) = get_char_pressed() {
-                ctx.store.get_mut::<Picker>().set_col(None);
-                if self.text.len() < 7 && c.is_ascii_hexdigit() {
+                if self.text.len() < 9 && c.is_ascii_hexdigit() {
                     self.text.push(c.to_ascii_uppercase());
-                    if self.text.len() == 7 {
-                        ctx.store.get_mut::<Picker>().set_col(Some(ColSelection::Rgba.col_from_hex_string(&self.text[1..]).to_rgba()));
-                        self.active = false;
-                    }
+                    changed = true;
                 }
             }
 
@@ -56,9 +53,20 @@ impl Node for HexInput {
                 if self.text.is_empty() {
                     self.text.push('#');
                 }
+                changed = true;
+            }
+
+            // Six digits sets the colour, but stays open in case an alpha byte follows
+            let complete = matches!(self.text.len(), 7 | 9);
+            if changed {
+                let col = complete.then(|| ColSelection::Rgba.col_from_hex_string(&self.text[1..]).to_rgba());
+                ctx.store.get_mut::<Picker>().set_col(col);
+            }
+            if self.text.len() == 9 || (complete && is_key_pressed(KeyCode::Enter)) {
+                self.active = false;
             }
         } else if let Some(col) = ctx.store.get_mut::<Picker>().get_col_rgba() {
-            self.text = ColSelection::format_rgba(col);
+            self.text = ColSelection::format_rgba_alpha(col);
         } else {
             self.text = "NA".to_string();
         }
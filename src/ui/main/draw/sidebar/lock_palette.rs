@@ -0,0 +1,39 @@
+use super::*;
+
+pub struct LockPalette {
+    pub rect: Rect
+}
+
+impl New for LockPalette {
+    fn new(_handler: &mut GenHandler) -> Self {
+        Self {
+            rect: Rect::new(10.0, 618.0, 133.0, 28.0),
+        }
+    }
+}
+
+impl Node for LockPalette {
+    fn update(&mut self, ctx: &mut AppContextHandler, node: &NodeStore) {
+        let palette = &mut ctx.save_data.workspace.palette;
+        let active = palette.locked;
+
+        if ui_button(
+            self.rect,
+            "Lock to palette",
+            if active { ENABLEDCOL } else { DISABLEDCOL },
+            if active { ENABLEDHOVERCOL } else { DISABLEDHOVERCOL },
+            node,
+            ctx.user_inputs
+        ) {
+            palette.locked = !palette.locked;
+        }
+    }
+
+    fn hit_detect(&mut self, pos: Vec2, node: &NodeStore, _store: &mut Store) -> Vec<WeakNode> {
+        if self.rect.contains(pos) {
+            vec![node.get_weak()]
+        } else {
+            vec![]
+        }
+    }
+}
@@ -5,12 +5,16 @@ mod save;
 mod eraser;
 mod save_grid;
 mod draw_state_button;
+mod lock_palette;
+mod remap_palette;
 
 use hex_input::HexInput;
 use save::Save;
 use eraser::Eraser;
 use save_grid::SaveGrid;
 use draw_state_button::DrawStateButton;
+use lock_palette::LockPalette;
+use remap_palette::RemapPalette;
 pub use draw_state_button::DrawState;
 
 
@@ -22,7 +26,7 @@ pub struct Sidebar {
 }
 
 impl New for Sidebar {
-    fn new(handler: &mut GenHandler) -> Self { // 0 is picker, 1 is hex input, 2 is save, 3 is save grid, 4 is eraser, 5 is screen picker, 6 is line, 7 is fill
+    fn new(handler: &mut GenHandler) -> Self { // 0 is picker, 1 is hex input, 2 is save, 3 is save grid, 4 is eraser, 5 is screen picker, 6 is line, 7 is fill, 8 is select, 9 is gradient, 10 is lock palette, 11 is remap palette
         handler.push_child::<PickerNode>();
         handler.push_child::<HexInput>();
         handler.push_child::<Save>();
@@ -31,8 +35,12 @@ impl New for Sidebar {
 
         handler.push_data(DrawState::Draw);
         handler.push_child_io::<DrawStateButton>(("Pick Colour", Rect::new(10.0, 448.0, 133.0, 28.0), DrawState::Picker));
-        handler.push_child_io::<DrawStateButton>(("Line", Rect::new(10.0, 516.0, 133.0, 28.0), DrawState::Line));
-        handler.push_child_io::<DrawStateButton>(("Fill", Rect::new(10.0, 550.0, 133.0, 28.0), DrawState::Fill));
+        handler.push_child_io::<DrawStateButton>(("Line", Rect::new(10.0, 516.0, 64.0, 28.0), DrawState::Line));
+        handler.push_child_io::<DrawStateButton>(("Fill", Rect::new(79.0, 516.0, 64.0, 28.0), DrawState::Fill));
+        handler.push_child_io::<DrawStateButton>(("Select", Rect::new(10.0, 550.0, 133.0, 28.0), DrawState::Select));
+        handler.push_child_io::<DrawStateButton>(("Gradient", Rect::new(10.0, 584.0, 133.0, 28.0), DrawState::Gradient));
+        handler.push_child::<LockPalette>();
+        handler.push_child::<RemapPalette>();
 
         Self {
             start_pos: vec2(0.0, 40.0),
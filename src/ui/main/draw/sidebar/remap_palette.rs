@@ -0,0 +1,44 @@
+use super::*;
+
+pub struct RemapPalette {
+    pub rect: Rect
+}
+
+impl New for RemapPalette {
+    fn new(_handler: &mut GenHandler) -> Self {
+        Self {
+            rect: Rect::new(10.0, 652.0, 133.0, 28.0),
+        }
+    }
+}
+
+impl Node for RemapPalette {
+    fn update(&mut self, ctx: &mut AppContextHandler, node: &NodeStore) {
+        if ctx.save_data.workspace.palette.is_empty() {
+            disabled_ui_button(self.rect, "Remap to palette", DISABLEDCOL);
+            return;
+        }
+
+        if ui_button(
+            self.rect,
+            "Remap to palette",
+            DISABLEDCOL,
+            DISABLEDHOVERCOL,
+            node,
+            ctx.user_inputs
+        ) {
+            History::record(ctx.store);
+            let col_sel = ctx.store.get::<Picker>().get_coltype();
+            let palette = &ctx.save_data.workspace.palette;
+            ctx.store.get_mut::<PixelArray>().map_cols(|col| palette.nearest(col, col_sel).unwrap_or(col));
+        }
+    }
+
+    fn hit_detect(&mut self, pos: Vec2, node: &NodeStore, _store: &mut Store) -> Vec<WeakNode> {
+        if self.rect.contains(pos) {
+            vec![node.get_weak()]
+        } else {
+            vec![]
+        }
+    }
+}
@@ -1,53 +1,146 @@
 use super::*;
 use save::{LastTouch, PrevTouch};
 
+const VISIBLE_ROWS: usize = 6;
+
 pub struct SaveGrid {
     rect: Rect,
-    saved_cols: Vec<[Option<[f32; 4]>; 4]>,
+    first_row: usize,
 }
 
 impl New for SaveGrid {
     fn new(_handler: &mut GenHandler) -> Self {
         Self {
-            rect: Rect::new(10.0, 60.0, 132.0, 198.0),
-            saved_cols: vec![[None; 4]; 6],
+            rect: Rect::new(10.0, 60.0, 137.0, 198.0),
+            first_row: 0,
         }
     }
 }
 
+impl SaveGrid {
+    fn slot_at(&self, pos: Vec2) -> Option<usize> {
+        if !self.rect.contains(pos) {
+            return None;
+        }
+
+        let x = ((pos.x - self.rect.x) / 33.0) as usize;
+        let y = ((pos.y - self.rect.y) / 33.0) as usize;
+        if x < PALETTE_WIDTH && y < VISIBLE_ROWS {
+            Some((y + self.first_row) * PALETTE_WIDTH + x)
+        } else {
+            None
+        }
+    }
+
+    fn slot_rect(&self, index: usize) -> Rect {
+        let x = 33.0 * (index % PALETTE_WIDTH) as f32 + self.rect.x;
+        let y = 33.0 * (index / PALETTE_WIDTH - self.first_row) as f32 + self.rect.y;
+        Rect::new(x, y, 28.0, 28.0)
+    }
+}
+
+fn draw_tooltip(pos: Vec2, swatch: &Swatch) {
+    let mut lines = vec![];
+    if !swatch.name.is_empty() {
+        lines.push(swatch.name.clone());
+    }
+    lines.push(ColSelection::format_rgba(swatch.col));
+    lines.push(ColSelection::Hsva.col_from_rgba_arr(swatch.col).describe());
+    lines.push(ColSelection::OkLab.col_from_rgba_arr(swatch.col).describe());
+
+    let width = lines.iter().map(|line| measure_text(line, None, 18, 1.0).width).fold(0.0, f32::max) + 16.0;
+    let height = lines.len() as f32 * 22.0 + 8.0;
+
+    draw_rectangle(pos.x, pos.y, width, height, WHITE);
+    draw_rectangle_lines(pos.x, pos.y, width, height, 2.0, DARKGRAY);
+    for (index, line) in lines.iter().enumerate() {
+        draw_text(line, pos.x + 8.0, pos.y + 22.0 * (index + 1) as f32, 18.0, BLACK);
+    }
+}
+
 impl Node for SaveGrid {
     fn update(&mut self, ctx: &mut AppContextHandler, node: &NodeStore) {
         let hovered = ctx.user_inputs.hover_test(node);
         let lasttouch = ctx.store.value::<LastTouch>();
         let prevlasttouch = ctx.store.value::<PrevTouch>();
+        let picker_col = ctx.store.get_mut::<Picker>().get_col_rgba();
+        let palette = &mut ctx.save_data.workspace.palette;
+
+        palette.fit_slots();
 
-        for (y, cols) in self.saved_cols.iter_mut().enumerate() {
-            for (x, col) in cols.iter_mut().enumerate() {
-                let x = 33.0 * x as f32 + 10.0;
-                let y = 33.0 * y as f32 + 60.0;
-                let rect = Rect::new(x, y, 28.0, 28.0);
-
-                if col_button(
-                    rect,
-                    hovered && rect.contains(ctx.user_inputs.mouse),
-                    ctx.user_inputs.left_let_go,
-                    if lasttouch { ENABLEDCOL } else { DISABLEDCOL },
-                    if lasttouch { ENABLEDHOVERCOL } else { DISABLEDHOVERCOL }
-                ) {
-                    if prevlasttouch {
-                        if let Some(new) = ctx.store.get_mut::<Picker>().get_col_rgba() {
-                            *col = Some(new);
-                        }
-                    } else {
-                        ctx.store.get_mut::<Picker>().set_col(*col);
+        // SCROLL
+        let rows = palette.saved_cols.len() / PALETTE_WIDTH;
+        if hovered {
+            let (_, my) = mouse_wheel();
+            if my > 0.0 {
+                self.first_row = self.first_row.saturating_sub(1);
+            } else if my < 0.0 {
+                self.first_row += 1;
+            }
+        }
+        self.first_row = self.first_row.min(rows.saturating_sub(VISIBLE_ROWS));
+
+        let hovered_slot = if hovered { self.slot_at(ctx.user_inputs.mouse) } else { None };
+        let touched_slot = if ctx.user_inputs.last_touch_test(node) { self.slot_at(ctx.user_inputs.lasttouch_mouse) } else { None };
+        let dragged_slot = touched_slot.filter(|&from|
+            ctx.user_inputs.left_mouse_down &&
+            palette.saved_cols.get(from).is_some_and(|d| d.is_some()) &&
+            hovered_slot != Some(from)
+        );
+
+        // EDIT
+        let mut new_col = None;
+        if let Some(to) = hovered_slot && to < palette.saved_cols.len() {
+            if ctx.user_inputs.left_let_go {
+                if prevlasttouch {
+                    if let Some(new) = picker_col {
+                        palette.saved_cols[to] = Some(Swatch::new(new));
                     }
+                } else if let Some(from) = touched_slot && from != to && palette.saved_cols.get(from).is_some_and(|d| d.is_some()) {
+                    palette.move_swatch(from, to);
+                } else {
+                    new_col = Some(palette.saved_cols[to].as_ref().map(|swatch| swatch.col));
                 }
+            } else if ctx.user_inputs.right_let_go {
+                palette.saved_cols[to] = None;
+                palette.fit_slots();
+            }
+        }
 
-                if let Some(col) = col {
-                    draw_rectangle(x + 4.0, y + 4.0, 20.0, 20.0, arr_to_macroquad(*col));
-                }
+        // DRAW
+        let first = self.first_row * PALETTE_WIDTH;
+        for (index, swatch) in palette.saved_cols.iter().enumerate().skip(first).take(VISIBLE_ROWS * PALETTE_WIDTH) {
+            let rect = self.slot_rect(index);
+
+            col_button(
+                rect,
+                hovered_slot == Some(index),
+                false,
+                if lasttouch { ENABLEDCOL } else { DISABLEDCOL },
+                if lasttouch { ENABLEDHOVERCOL } else { DISABLEDHOVERCOL }
+            );
+
+            if let Some(swatch) = swatch && dragged_slot != Some(index) {
+                draw_rectangle(rect.x + 4.0, rect.y + 4.0, 20.0, 20.0, arr_to_macroquad(swatch.col));
             }
         }
+
+        if rows > VISIBLE_ROWS {
+            let height = self.rect.h * VISIBLE_ROWS as f32 / rows as f32;
+            let y = self.rect.y + self.rect.h * self.first_row as f32 / rows as f32;
+            draw_rectangle(self.rect.x + self.rect.w - 4.0, y, 4.0, height, DARKGRAY);
+        }
+
+        if let Some(from) = dragged_slot && let Some(swatch) = &palette.saved_cols[from] {
+            let mouse = ctx.user_inputs.mouse;
+            draw_rectangle(mouse.x - 10.0, mouse.y - 10.0, 20.0, 20.0, arr_to_macroquad(swatch.col));
+        } else if let Some(index) = hovered_slot && let Some(Some(swatch)) = palette.saved_cols.get(index) {
+            draw_tooltip(vec2(155.0, ctx.user_inputs.mouse.y), swatch);
+        }
+
+        if let Some(col) = new_col {
+            ctx.store.get_mut::<Picker>().set_col(col);
+        }
     }
 
     fn hit_detect(&mut self, pos: Vec2, node: &NodeStore, _store: &mut Store) -> Vec<WeakNode> {
@@ -57,4 +150,4 @@ impl Node for SaveGrid {
             vec![]
         }
     }
-}
\ No newline at end of file
+}
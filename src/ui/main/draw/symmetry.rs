@@ -0,0 +1,187 @@
+use std::fmt::Display;
+
+use super::*;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SymmetryMode {
+    Off,
+    Horizontal,
+    Vertical,
+    FourWay,
+    Radial,
+}
+
+impl Display for SymmetryMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Off => write!(f, "Off"),
+            Self::Horizontal => write!(f, "Horizontal"),
+            Self::Vertical => write!(f, "Vertical"),
+            Self::FourWay => write!(f, "4-way"),
+            Self::Radial => write!(f, "Radial"),
+        }
+    }
+}
+
+impl SymmetryMode {
+    pub fn toggle(&self) -> Self {
+        match self {
+            Self::Off => Self::Horizontal,
+            Self::Horizontal => Self::Vertical,
+            Self::Vertical => Self::FourWay,
+            Self::FourWay => Self::Radial,
+            Self::Radial => Self::Off,
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct Symmetry {
+    pub mode: SymmetryMode,
+    /// Twice the world position of the axes, so they can sit on a pixel edge or through a pixel's centre
+    pub axis: [i32; 2],
+    pub folds: u8,
+}
+
+impl Default for Symmetry {
+    fn default() -> Self {
+        Self {
+            mode: SymmetryMode::Off,
+            axis: [0, 0],
+            folds: 6,
+        }
+    }
+}
+
+fn to_i16(value: i32) -> i16 {
+    value.clamp(i16::MIN as i32, i16::MAX as i32) as i16
+}
+
+impl Symmetry {
+    fn centre(&self) -> WorldPos {
+        WorldPos(self.axis[0] as f32 / 2.0, self.axis[1] as f32 / 2.0)
+    }
+
+    /// `pos` and each of its mirror images, always in the same order so two lists can be zipped into lines
+    pub fn images(&self, pos: [i16; 2]) -> Vec<[i16; 2]> {
+        let [x, y] = pos.map(|d| d as i32);
+        let mirror_x = to_i16(self.axis[0] - x - 1);
+        let mirror_y = to_i16(self.axis[1] - y - 1);
+
+        match self.mode {
+            SymmetryMode::Off => vec![pos],
+            SymmetryMode::Horizontal => vec![pos, [mirror_x, pos[1]]],
+            SymmetryMode::Vertical => vec![pos, [pos[0], mirror_y]],
+            SymmetryMode::FourWay => vec![pos, [mirror_x, pos[1]], [pos[0], mirror_y], [mirror_x, mirror_y]],
+            SymmetryMode::Radial => {
+                let centre = self.centre();
+                let offset = vec2(x as f32 + 0.5 - centre.0, y as f32 + 0.5 - centre.1);
+                (0..self.folds).map(|fold| {
+                    let rotated = Vec2::from_angle(std::f32::consts::TAU * fold as f32 / self.folds as f32).rotate(offset);
+                    [
+                        to_i16((centre.0 + rotated.x).floor() as i32),
+                        to_i16((centre.1 + rotated.y).floor() as i32),
+                    ]
+                }).collect()
+            }
+        }
+    }
+
+    pub fn draw(&self, transform: &Transform) {
+        let centre = transform.world_to_screen(&self.centre());
+        let (width, height) = transform.window_dims;
+        let col = Color::new(0.9, 0.2, 0.6, 0.8);
+
+        match self.mode {
+            SymmetryMode::Off => return,
+            SymmetryMode::Horizontal => draw_line(centre.0, 0.0, centre.0, height, 2.0, col),
+            SymmetryMode::Vertical => draw_line(0.0, centre.1, width, centre.1, 2.0, col),
+            SymmetryMode::FourWay => {
+                draw_line(centre.0, 0.0, centre.0, height, 2.0, col);
+                draw_line(0.0, centre.1, width, centre.1, 2.0, col);
+            },
+            SymmetryMode::Radial => {
+                let length = width + height;
+                for fold in 0..self.folds {
+                    let dir = Vec2::from_angle(std::f32::consts::TAU * fold as f32 / self.folds as f32 - std::f32::consts::FRAC_PI_2);
+                    draw_line(centre.0, centre.1, centre.0 + dir.x * length, centre.1 + dir.y * length, 1.0, col);
+                }
+            }
+        }
+        draw_circle(centre.0, centre.1, 4.0, col);
+    }
+}
+
+pub struct SymmetryPanel {
+    rect: Rect,
+    visible: bool,
+}
+
+impl New for SymmetryPanel {
+    fn new(handler: &mut GenHandler) -> Self {
+        handler.push_data(Symmetry::default());
+
+        Self {
+            rect: Rect::default(),
+            visible: false,
+        }
+    }
+}
+
+impl Node for SymmetryPanel {
+    fn update(&mut self, ctx: &mut AppContextHandler, node: &NodeStore) {
+        if ctx.user_inputs.instruction_pressed(ShortcutInstruction::ToggleSymmetryPanel) {
+            self.visible = !self.visible;
+        }
+        if !self.visible {
+            self.rect = Rect::default();
+            return;
+        }
+
+        self.rect = ctx.store.get_mut::<PanelLayout>().place(206.0);
+        let y = self.rect.y;
+        let x = self.rect.x + 10.0;
+        draw_rectangle(self.rect.x, self.rect.y, self.rect.w, self.rect.h, WHITE);
+        draw_rectangle_lines(self.rect.x, self.rect.y, self.rect.w, self.rect.h, 2.0, DARKGRAY);
+        draw_text("Symmetry", x, y + 22.0, 18.0, BLACK);
+
+        let bounds = ctx.store.get::<PixelArray>().get_bounds();
+        let symmetry = ctx.store.get_mut::<Symmetry>();
+
+        if sub_ui_button(Rect::new(x, y + 34.0, 200.0, 28.0), &format!("Mode: {}", symmetry.mode), DISABLEDCOL, DISABLEDHOVERCOL, node, ctx.user_inputs) {
+            symmetry.mode = symmetry.mode.toggle();
+        }
+
+        if sub_ui_button(Rect::new(x, y + 68.0, 200.0, 28.0), "Centre on drawing", DISABLEDCOL, DISABLEDHOVERCOL, node, ctx.user_inputs) {
+            // The bounds are the outermost pixels, so the far edge is one past them
+            symmetry.axis = [
+                (bounds[0].0 + bounds[1].0 + 1.0) as i32,
+                (bounds[0].1 + bounds[1].1 + 1.0) as i32,
+            ];
+        }
+
+        if symmetry.mode == SymmetryMode::Radial && let Some(value) = slider(
+            ENABLEDCOL,
+            DISABLEDCOL,
+            Rect::new(x, y + 122.0, 200.0, 18.0),
+            &format!("Folds: {}", symmetry.folds),
+            symmetry.folds as f32,
+            2.0,
+            10.0,
+            ctx.user_inputs,
+            node
+        ) {
+            symmetry.folds = value.round() as u8;
+        }
+
+        multiline_text(Rect::new(x, y + 162.0, 200.0, 40.0), "Alt + click the canvas to move the axis.", 14);
+    }
+
+    fn hit_detect(&mut self, pos: Vec2, node: &NodeStore, _store: &mut Store) -> Vec<WeakNode> {
+        if self.rect.contains(pos) {
+            vec![node.get_weak()]
+        } else {
+            vec![]
+        }
+    }
+}
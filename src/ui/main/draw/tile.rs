@@ -0,0 +1,123 @@
+use super::*;
+
+#[derive(Clone, Copy)]
+pub struct TileMode {
+    pub enabled: bool,
+    pub size: [i16; 2],
+}
+
+impl Default for TileMode {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            size: [16, 16],
+        }
+    }
+}
+
+impl TileMode {
+    pub fn wrap(&self) -> Option<[i16; 2]> {
+        self.enabled.then_some(self.size)
+    }
+
+    /// Draws the pixels inside the tile again on every side of it, with the tile's edge outlined
+    pub fn draw(&self, pixels: &PixelArray, transform: &Transform) {
+        if !self.enabled {
+            return;
+        }
+        let [w, h] = self.size;
+        let size = transform.size();
+
+        for pixel in pixels.iter().filter(|pixel| (0..w).contains(&pixel.pos[0]) && (0..h).contains(&pixel.pos[1])) {
+            for (dx, dy) in (-1..=1).flat_map(|dx| (-1..=1).map(move |dy| (dx, dy))).filter(|&d| d != (0, 0)) {
+                let world = WorldPos((pixel.pos[0] + dx * w) as f32, (pixel.pos[1] + dy * h) as f32);
+                let Some(pos) = transform.world_to_screen_filter(&world) else { continue };
+                draw_rectangle(pos.0, pos.1, size, size, arr_to_macroquad(pixel.col));
+            }
+        }
+
+        let start = transform.world_to_screen(&WorldPos(0.0, 0.0));
+        draw_rectangle_lines(start.0, start.1, w as f32 * size, h as f32 * size, 2.0, Color::new(0.2, 0.6, 0.9, 0.9));
+    }
+}
+
+pub struct TilePanel {
+    rect: Rect,
+    visible: bool,
+}
+
+impl New for TilePanel {
+    fn new(handler: &mut GenHandler) -> Self {
+        handler.push_data(TileMode::default());
+
+        Self {
+            rect: Rect::default(),
+            visible: false,
+        }
+    }
+}
+
+impl Node for TilePanel {
+    fn update(&mut self, ctx: &mut AppContextHandler, node: &NodeStore) {
+        if ctx.user_inputs.instruction_pressed(ShortcutInstruction::ToggleTilePanel) {
+            self.visible = !self.visible;
+        }
+        if !self.visible {
+            self.rect = Rect::default();
+            return;
+        }
+
+        self.rect = ctx.store.get_mut::<PanelLayout>().place(196.0);
+        let y = self.rect.y;
+        let x = self.rect.x + 10.0;
+        draw_rectangle(self.rect.x, self.rect.y, self.rect.w, self.rect.h, WHITE);
+        draw_rectangle_lines(self.rect.x, self.rect.y, self.rect.w, self.rect.h, 2.0, DARKGRAY);
+        draw_text("Tile mode", x, y + 22.0, 18.0, BLACK);
+
+        let bounds = ctx.store.get::<PixelArray>().get_bounds();
+        let tile = ctx.store.get_mut::<TileMode>();
+
+        if sub_ui_button(
+            Rect::new(x, y + 34.0, 200.0, 28.0),
+            if tile.enabled { "Tiling: On" } else { "Tiling: Off" },
+            if tile.enabled { ENABLEDCOL } else { DISABLEDCOL },
+            if tile.enabled { ENABLEDHOVERCOL } else { DISABLEDHOVERCOL },
+            node,
+            ctx.user_inputs
+        ) {
+            tile.enabled = !tile.enabled;
+        }
+
+        for (axis, label) in ["Width", "Height"].iter().enumerate() {
+            if let Some(value) = slider(
+                ENABLEDCOL,
+                DISABLEDCOL,
+                Rect::new(x, y + 90.0 + 40.0 * axis as f32, 200.0, 14.0),
+                &format!("{label}: {}", tile.size[axis]),
+                tile.size[axis] as f32,
+                1.0,
+                127.0,
+                ctx.user_inputs,
+                node
+            ) {
+                tile.size[axis] = value.round() as i16;
+            }
+        }
+
+        // The tile always starts at 0, 0 so only its far corner can follow the drawing
+        if sub_ui_button(Rect::new(x, y + 160.0, 200.0, 28.0), "Fit to drawing", DISABLEDCOL, DISABLEDHOVERCOL, node, ctx.user_inputs) {
+            tile.size = [
+                (bounds[1].0 as i16).max(0) + 1,
+                (bounds[1].1 as i16).max(0) + 1,
+            ];
+        }
+    }
+
+    fn hit_detect(&mut self, pos: Vec2, node: &NodeStore, _store: &mut Store) -> Vec<WeakNode> {
+        if self.rect.contains(pos) {
+            vec![node.get_weak()]
+        } else {
+            vec![]
+        }
+    }
+}
@@ -0,0 +1,282 @@
+use rfd::FileDialog;
+use image::RgbaImage;
+
+use super::*;
+use crate::animation::{Animation, Frame, export::{self, AnimationFormat}, sheet};
+
+const ONION_TINTS: [[f32; 3]; 2] = [[1.0, 0.2, 0.2], [0.2, 0.4, 1.0]];
+
+/// Whether the frames either side of the current one are drawn faded behind it
+#[tuple_deref]
+pub struct OnionSkin(pub bool);
+
+pub fn draw_onion_skin(store: &Store, transform: &Transform) {
+    if !**store.get::<OnionSkin>() {
+        return;
+    }
+    for (frame, tint) in store.get::<Animation>().neighbours().into_iter().zip(ONION_TINTS) {
+        if let Some(frame) = frame {
+            frame.draw_onion(transform, tint);
+        }
+    }
+}
+
+pub struct Timeline {
+    rect: Rect,
+    onion_skin: bool,
+    /// When playback started and the frame to go back to once it stops
+    playing: Option<(f64, usize)>,
+    format: usize,
+    message: Option<String>,
+    /// A sheet without an atlas, waiting for a cell size to be sliced by
+    slicing: Option<(RgbaImage, [u32; 2])>,
+}
+
+impl New for Timeline {
+    fn new(handler: &mut GenHandler) -> Self {
+        handler.push_data(Animation::default());
+        handler.push_data(OnionSkin(true));
+
+        Self {
+            rect: Rect::default(),
+            onion_skin: true,
+            playing: None,
+            format: 0,
+            message: None,
+            slicing: None,
+        }
+    }
+}
+
+impl Timeline {
+    fn select(ctx: &mut AppContextHandler, index: usize) {
+        let mut active = std::mem::take(ctx.store.get_mut::<PixelArray>());
+        ctx.store.get_mut::<Animation>().select(index, &mut active);
+        ctx.store.overwrite(active);
+    }
+
+    fn edit(ctx: &mut AppContextHandler, f: impl FnOnce(&mut Animation, &mut PixelArray)) {
+        let mut active = std::mem::take(ctx.store.get_mut::<PixelArray>());
+        f(ctx.store.get_mut::<Animation>(), &mut active);
+        ctx.store.overwrite(active);
+        ctx.store.get_mut::<History>().clear();
+    }
+
+    fn slice_rect() -> Rect {
+        Rect::new(173.0, screen_height() - 240.0, 240.0, 150.0)
+    }
+
+    fn import(&mut self, ctx: &mut AppContextHandler) {
+        let Some(path) = FileDialog::new()
+            .set_title("Import sprite sheet")
+            .add_filter("Image", IMAGE_EXTENSIONS)
+            .pick_file()
+        else { return };
+
+        let sheet = match sheet::load(&path) {
+            Ok(sheet) => sheet,
+            Err(err) => {
+                self.message = Some(err);
+                return;
+            }
+        };
+
+        match sheet::slice_by_atlas(&path, &sheet) {
+            Some(Ok(frames)) => self.append(ctx, frames),
+            Some(Err(err)) => self.message = Some(err),
+            None => {
+                // Most sheets are a single row of square frames
+                let cell = sheet.width().min(sheet.height());
+                self.slicing = Some((sheet, [cell, cell]));
+            }
+        }
+    }
+
+    fn append(&mut self, ctx: &mut AppContextHandler, frames: Vec<Frame>) {
+        self.message = Some(format!("Imported {} frames.", frames.len()));
+        Self::edit(ctx, |animation, active| animation.append(frames, active));
+    }
+
+    fn update_slicing(&mut self, ctx: &mut AppContextHandler, node: &NodeStore) {
+        let Some((sheet, cell)) = &mut self.slicing else { return };
+        let rect = Self::slice_rect();
+        let x = rect.x + 10.0;
+        draw_rectangle(rect.x, rect.y, rect.w, rect.h, WHITE);
+        draw_rectangle_lines(rect.x, rect.y, rect.w, rect.h, 2.0, DARKGRAY);
+        draw_text(&format!("Slice {} x {} sheet", sheet.width(), sheet.height()), x, rect.y + 22.0, 18.0, BLACK);
+
+        for (axis, (label, max)) in [("Cell width", sheet.width()), ("Cell height", sheet.height())].into_iter().enumerate() {
+            if let Some(value) = slider(
+                ENABLEDCOL,
+                DISABLEDCOL,
+                Rect::new(x, rect.y + 52.0 + 40.0 * axis as f32, 220.0, 12.0),
+                &format!("{label}: {}", cell[axis]),
+                cell[axis] as f32,
+                1.0,
+                (max - 1).max(1) as f32,
+                ctx.user_inputs,
+                node
+            ) {
+                cell[axis] = value.round() as u32;
+            }
+        }
+
+        let cells = (sheet.width() / cell[0]) * (sheet.height() / cell[1]);
+        if sub_ui_button(Rect::new(x, rect.y + 114.0, 140.0, 26.0), &format!("Import {cells} cells"), ENABLEDCOL, ENABLEDHOVERCOL, node, ctx.user_inputs) {
+            let frames = sheet::slice_by_cell(sheet, *cell);
+            self.slicing = None;
+            self.append(ctx, frames);
+        } else if sub_ui_button(Rect::new(x + 144.0, rect.y + 114.0, 76.0, 26.0), "Cancel", DISABLEDCOL, DISABLEDHOVERCOL, node, ctx.user_inputs) {
+            self.slicing = None;
+        }
+    }
+
+    fn export(&mut self, ctx: &mut AppContextHandler) {
+        let format = AnimationFormat::ALL[self.format];
+        let Some(mut path) = FileDialog::new()
+            .set_title("Export animation")
+            .add_filter(format.name(), &[format.extension()])
+            .save_file()
+        else { return };
+
+        if path.extension().is_none() {
+            path.set_extension(format.extension());
+        }
+        let active = ctx.store.get::<PixelArray>();
+        let frames = ctx.store.get::<Animation>().frames(active).collect::<Vec<_>>();
+        self.message = Some(match export::export(&path, format, &frames) {
+            Ok(()) => format!("Saved {} frames.", frames.len()),
+            Err(err) => err
+        });
+    }
+}
+
+impl Node for Timeline {
+    fn update(&mut self, ctx: &mut AppContextHandler, node: &NodeStore) {
+        self.rect = Rect::new(163.0, screen_height() - 80.0, screen_width() - 173.0, 70.0);
+        let x = self.rect.x + 10.0;
+        let y = self.rect.y;
+        draw_rectangle(self.rect.x, self.rect.y, self.rect.w, self.rect.h, WHITE);
+        draw_rectangle_lines(self.rect.x, self.rect.y, self.rect.w, self.rect.h, 2.0, DARKGRAY);
+
+        // PLAYBACK
+        if let Some((start, _)) = self.playing {
+            let time = ((get_time() - start) * 1000.0) as u32;
+            let index = ctx.store.get::<Animation>().frame_at(time);
+            Self::select(ctx, index);
+        }
+        ctx.store.overwrite(OnionSkin(self.onion_skin && self.playing.is_none()));
+
+        match ctx.user_inputs.pressed_instruction {
+            ShortcutInstruction::PreviousFrame if self.playing.is_none() => {
+                let current = ctx.store.get::<Animation>().current();
+                Self::select(ctx, current.saturating_sub(1));
+            },
+            ShortcutInstruction::NextFrame if self.playing.is_none() => {
+                let current = ctx.store.get::<Animation>().current();
+                Self::select(ctx, current + 1);
+            },
+            _ => {}
+        }
+
+        // FRAMES
+        let animation = ctx.store.get::<Animation>();
+        let (len, current) = (animation.len(), animation.current());
+        let visible = ((self.rect.w - 250.0) / 28.0).max(1.0) as usize;
+        let first = (current + 1).saturating_sub(visible);
+        for index in first..len.min(first + visible) {
+            let rect = Rect::new(x + 28.0 * (index - first) as f32, y + 8.0, 24.0, 24.0);
+            let selected = index == current;
+            if sub_ui_button(
+                rect,
+                &(index + 1).to_string(),
+                if selected { ENABLEDCOL } else { DISABLEDCOL },
+                if selected { ENABLEDHOVERCOL } else { DISABLEDHOVERCOL },
+                node,
+                ctx.user_inputs
+            ) && self.playing.is_none() {
+                Self::select(ctx, index);
+            }
+        }
+
+        let duration = ctx.store.get::<Animation>().duration(current);
+        if let Some(value) = slider(
+            ENABLEDCOL,
+            DISABLEDCOL,
+            Rect::new(self.rect.x + self.rect.w - 230.0, y + 22.0, 210.0, 10.0),
+            &format!("Frame {} of {len}: {duration} ms", current + 1),
+            duration as f32,
+            10.0,
+            990.0,
+            ctx.user_inputs,
+            node
+        ) {
+            ctx.store.get_mut::<Animation>().set_duration(current, (value / 10.0).round() as u32 * 10);
+        }
+
+        // BUTTONS
+        let button = |index: f32, width: f32| Rect::new(x + 64.0 * index, y + 38.0, width, 26.0);
+        if self.playing.is_none() {
+            if sub_ui_button(button(0.0, 60.0), "New", DISABLEDCOL, DISABLEDHOVERCOL, node, ctx.user_inputs) {
+                Self::edit(ctx, |animation, active| animation.insert(active, false));
+            }
+            if sub_ui_button(button(1.0, 60.0), "Copy", DISABLEDCOL, DISABLEDHOVERCOL, node, ctx.user_inputs) {
+                Self::edit(ctx, |animation, active| animation.insert(active, true));
+            }
+            if sub_ui_button(button(2.0, 60.0), "Delete", DISABLEDCOL, DISABLEDHOVERCOL, node, ctx.user_inputs) {
+                Self::edit(ctx, |animation, active| animation.remove(active));
+            }
+            if sub_ui_button(button(3.0, 28.0), "<", DISABLEDCOL, DISABLEDHOVERCOL, node, ctx.user_inputs) {
+                ctx.store.get_mut::<Animation>().shift(-1);
+                ctx.store.get_mut::<History>().clear();
+            }
+            if sub_ui_button(Rect::new(x + 224.0, y + 38.0, 28.0, 26.0), ">", DISABLEDCOL, DISABLEDHOVERCOL, node, ctx.user_inputs) {
+                ctx.store.get_mut::<Animation>().shift(1);
+                ctx.store.get_mut::<History>().clear();
+            }
+        } else {
+            for (index, label) in ["New", "Copy", "Delete"].iter().enumerate() {
+                disabled_ui_button(button(index as f32, 60.0), label, DISABLEDCOL);
+            }
+            disabled_ui_button(button(3.0, 28.0), "<", DISABLEDCOL);
+            disabled_ui_button(Rect::new(x + 224.0, y + 38.0, 28.0, 26.0), ">", DISABLEDCOL);
+        }
+
+        let playing = self.playing.is_some();
+        if sub_ui_button(button(4.0, 60.0), if playing { "Stop" } else { "Play" }, if playing { ENABLEDCOL } else { DISABLEDCOL }, if playing { ENABLEDHOVERCOL } else { DISABLEDHOVERCOL }, node, ctx.user_inputs) {
+            match self.playing.take() {
+                Some((_, resume)) => Self::select(ctx, resume),
+                None => self.playing = Some((get_time(), current)),
+            }
+        }
+        if sub_ui_button(button(5.0, 60.0), "Onion", if self.onion_skin { ENABLEDCOL } else { DISABLEDCOL }, if self.onion_skin { ENABLEDHOVERCOL } else { DISABLEDHOVERCOL }, node, ctx.user_inputs) {
+            self.onion_skin = !self.onion_skin;
+        }
+
+        // EXPORT
+        if sub_ui_button(button(6.0, 100.0), AnimationFormat::ALL[self.format].name(), DISABLEDCOL, DISABLEDHOVERCOL, node, ctx.user_inputs) {
+            self.format = (self.format + 1) % AnimationFormat::ALL.len();
+        }
+        if sub_ui_button(Rect::new(x + 488.0, y + 38.0, 60.0, 26.0), "Export", ENABLEDCOL, ENABLEDHOVERCOL, node, ctx.user_inputs) {
+            self.export(ctx);
+        }
+
+        if sub_ui_button(Rect::new(x + 552.0, y + 38.0, 60.0, 26.0), "Import", DISABLEDCOL, DISABLEDHOVERCOL, node, ctx.user_inputs) && self.playing.is_none() {
+            self.import(ctx);
+        }
+
+        if let Some(message) = &self.message {
+            draw_text(&message.replace('\n', " "), x, y - 8.0, 16.0, DARKGRAY);
+        }
+
+        self.update_slicing(ctx, node);
+    }
+
+    fn hit_detect(&mut self, pos: Vec2, node: &NodeStore, _store: &mut Store) -> Vec<WeakNode> {
+        if self.rect.contains(pos) || (self.slicing.is_some() && Self::slice_rect().contains(pos)) {
+            vec![node.get_weak()]
+        } else {
+            vec![]
+        }
+    }
+}
@@ -0,0 +1,156 @@
+use super::*;
+
+pub struct TransformPanel {
+    rect: Rect,
+    visible: bool,
+    degrees: f32,
+    rotsprite: bool,
+    factor: u8,
+    offset: [i16; 2],
+    message: Option<String>,
+}
+
+impl New for TransformPanel {
+    fn new(_handler: &mut GenHandler) -> Self {
+        Self {
+            rect: Rect::default(),
+            visible: false,
+            degrees: 45.0,
+            rotsprite: true,
+            factor: 2,
+            offset: [0, 0],
+            message: None,
+        }
+    }
+}
+
+impl TransformPanel {
+    /// Applies `op` to the selection, or the whole drawing without one, as a single undo step
+    fn apply(&mut self, ctx: &mut AppContextHandler, op: Geometric) {
+        let selection = **ctx.store.get::<ActiveSelection>();
+        let mut pixels = ctx.store.get::<PixelArray>().clone();
+        match pixels.transform(op, selection.map(|d| d.corners())) {
+            Ok(corners) => {
+                History::record(ctx.store);
+                ctx.store.overwrite(pixels);
+                if selection.is_some() {
+                    ctx.store.overwrite(ActiveSelection(corners.map(|[min, max]| Selection::new(min, max))));
+                }
+                self.message = None;
+            },
+            Err(err) => self.message = Some(err),
+        }
+    }
+}
+
+impl Node for TransformPanel {
+    fn update(&mut self, ctx: &mut AppContextHandler, node: &NodeStore) {
+        match ctx.user_inputs.pressed_instruction {
+            ShortcutInstruction::Undo => History::step(ctx.store, false),
+            ShortcutInstruction::Redo => History::step(ctx.store, true),
+            ShortcutInstruction::ToggleTransformPanel => self.visible = !self.visible,
+            _ => {}
+        }
+        if !self.visible {
+            self.rect = Rect::default();
+            return;
+        }
+
+        self.rect = ctx.store.get_mut::<PanelLayout>().place(270.0);
+        let x = self.rect.x + 10.0;
+        let y = self.rect.y;
+        let column = |index: f32| x + 68.0 * index;
+        draw_rectangle(self.rect.x, self.rect.y, self.rect.w, self.rect.h, WHITE);
+        draw_rectangle_lines(self.rect.x, self.rect.y, self.rect.w, self.rect.h, 2.0, DARKGRAY);
+        let scope = if ctx.store.get::<ActiveSelection>().is_some() { "selection" } else { "drawing" };
+        draw_text(&format!("Transform {scope}"), x, y + 22.0, 18.0, BLACK);
+
+        let buttons = [
+            ("Flip H", Geometric::FlipHorizontal, column(0.0), y + 30.0),
+            ("Flip V", Geometric::FlipVertical, column(1.0), y + 30.0),
+            ("180°", Geometric::Rotate180, column(2.0), y + 30.0),
+            ("90°", Geometric::Rotate90, column(0.0), y + 62.0),
+            ("270°", Geometric::Rotate270, column(1.0), y + 62.0),
+        ];
+        for (label, op, bx, by) in buttons {
+            if sub_ui_button(Rect::new(bx, by, 64.0, 26.0), label, DISABLEDCOL, DISABLEDHOVERCOL, node, ctx.user_inputs) {
+                self.apply(ctx, op);
+            }
+        }
+        if sub_ui_button(Rect::new(column(2.0), y + 62.0, 64.0, 26.0), if self.rotsprite { "RotSprite" } else { "Nearest" }, DISABLEDCOL, DISABLEDHOVERCOL, node, ctx.user_inputs) {
+            self.rotsprite = !self.rotsprite;
+        }
+
+        // ROTATE
+        if let Some(value) = slider(
+            ENABLEDCOL,
+            DISABLEDCOL,
+            Rect::new(x, y + 110.0, 132.0, 12.0),
+            &format!("Angle: {}°", self.degrees),
+            self.degrees,
+            -180.0,
+            360.0,
+            ctx.user_inputs,
+            node
+        ) {
+            self.degrees = value.round();
+        }
+        if sub_ui_button(Rect::new(column(2.0), y + 100.0, 64.0, 26.0), "Rotate", DISABLEDCOL, DISABLEDHOVERCOL, node, ctx.user_inputs) {
+            self.apply(ctx, Geometric::Rotate { degrees: self.degrees, rotsprite: self.rotsprite });
+        }
+
+        // SCALE
+        if let Some(value) = slider(
+            ENABLEDCOL,
+            DISABLEDCOL,
+            Rect::new(x, y + 156.0, 64.0, 12.0),
+            &format!("Scale: {}", self.factor),
+            self.factor as f32,
+            2.0,
+            6.0,
+            ctx.user_inputs,
+            node
+        ) {
+            self.factor = value.round() as u8;
+        }
+        if sub_ui_button(Rect::new(column(1.0), y + 146.0, 64.0, 26.0), "Up", DISABLEDCOL, DISABLEDHOVERCOL, node, ctx.user_inputs) {
+            self.apply(ctx, Geometric::ScaleUp(self.factor));
+        }
+        if sub_ui_button(Rect::new(column(2.0), y + 146.0, 64.0, 26.0), "Down", DISABLEDCOL, DISABLEDHOVERCOL, node, ctx.user_inputs) {
+            self.apply(ctx, Geometric::ScaleDown(self.factor));
+        }
+
+        // TRANSLATE
+        for (axis, label) in ["X", "Y"].iter().enumerate() {
+            if let Some(value) = slider(
+                ENABLEDCOL,
+                DISABLEDCOL,
+                Rect::new(column(axis as f32), y + 202.0, 64.0, 12.0),
+                &format!("{label}: {}", self.offset[axis]),
+                self.offset[axis] as f32,
+                -32.0,
+                64.0,
+                ctx.user_inputs,
+                node
+            ) {
+                self.offset[axis] = value.round() as i16;
+            }
+        }
+        if sub_ui_button(Rect::new(column(2.0), y + 192.0, 64.0, 26.0), "Move", DISABLEDCOL, DISABLEDHOVERCOL, node, ctx.user_inputs) {
+            self.apply(ctx, Geometric::Translate(self.offset));
+        }
+
+        match &self.message {
+            Some(message) => multiline_text(Rect::new(x, y + 240.0, 200.0, 30.0), message, 14),
+            None => { draw_text("Ctrl + Z to undo, Ctrl + Y to redo", x, y + 240.0, 14.0, DARKGRAY); },
+        }
+    }
+
+    fn hit_detect(&mut self, pos: Vec2, node: &NodeStore, _store: &mut Store) -> Vec<WeakNode> {
+        if self.rect.contains(pos) {
+            vec![node.get_weak()]
+        } else {
+            vec![]
+        }
+    }
+}
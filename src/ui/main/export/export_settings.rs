@@ -1,19 +1,76 @@
-use std::path::PathBuf;
+use std::{fmt::Display, path::PathBuf};
 
 use super::*;
 
 pub struct ExportSettings {
     pub path: Option<PathBuf>,
+    /// Where to look inside a zip file, with `*` matching any one folder
+    pub archive_subpath: String,
     pub process: ProcessSettings,
     pub place: PlaceSettings,
+    pub placement_map: PlacementMapFormat,
 }
 
 impl ExportSettings {
-    pub fn new(path: Option<PathBuf>, temperature: f32, averaging_col: ColSelection, distance_col: ColSelection, pixel_size: u32, accept_transparent: f32) -> Self {
+    pub fn new(path: Option<PathBuf>, temperature: f32, averaging_col: ColSelection, distance_col: ColSelection, tile_size: TileSize, accept_transparent: f32) -> Self {
         Self {
             path,
-            process: ProcessSettings { averaging_col, pixel_size, accept_transparent, changed_this_frame: false },
-            place: PlaceSettings { distance_col, temperature, rect: None }
+            archive_subpath: String::new(),
+            process: ProcessSettings {
+                averaging_col,
+                tile_size,
+                fit: TileFit::Stretch,
+                accept_transparent,
+                tolerant: true,
+                follow_symlinks: true,
+                max_depth: None,
+                changed_this_frame: false
+            },
+            place: PlaceSettings {
+                distance_col,
+                metric: DistanceMetric::Euclidean,
+                lightness_weight: 1.0,
+                chroma_weight: 1.0,
+                background: AlphaBackground::Ignore,
+                temperature,
+                rect: None
+            },
+            placement_map: PlacementMapFormat::None
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum PlacementMapFormat {
+    None,
+    Json,
+    Csv
+}
+
+impl PlacementMapFormat {
+    pub fn toggle(&self) -> Self {
+        match self {
+            Self::None => Self::Json,
+            Self::Json => Self::Csv,
+            Self::Csv => Self::None,
+        }
+    }
+
+    pub fn extension(&self) -> Option<&'static str> {
+        match self {
+            Self::None => None,
+            Self::Json => Some("json"),
+            Self::Csv => Some("csv"),
+        }
+    }
+}
+
+impl Display for PlacementMapFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::None => write!(f, "Off"),
+            Self::Json => write!(f, "JSON"),
+            Self::Csv => write!(f, "CSV"),
         }
     }
 }
@@ -21,18 +78,169 @@ impl ExportSettings {
 #[derive(Clone, Copy)]
 pub struct ProcessSettings {
     pub averaging_col: ColSelection,
-    pub pixel_size: u32,
+    pub tile_size: TileSize,
+    pub fit: TileFit,
     pub accept_transparent: f32,
+    /// Skips files that can't be read, listing them once loading is done, instead of stopping
+    pub tolerant: bool,
+    pub follow_symlinks: bool,
+    /// How many folders deep to look for textures, `None` for no limit
+    pub max_depth: Option<usize>,
     pub changed_this_frame: bool
 }
 
+impl ProcessSettings {
+    pub fn validate(&self) -> Result<(), String> {
+        match self.tile_size {
+            TileSize::Fixed([w, h]) if w == 0 || h == 0 => Err(format!("Tiles can't be {w} x {h} pixels, both sides need to be at least 1.")),
+            _ => Ok(())
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum TileSize {
+    /// Width and height every texture is resized to
+    Fixed([u32; 2]),
+    /// Textures are used as they are, which needs them all to be the same size
+    Native,
+}
+
+/// How a texture is made to fit a tile of a different shape
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum TileFit {
+    Stretch,
+    /// Scaled to cover the tile, cutting off whatever hangs over
+    Crop,
+    /// Scaled to fit inside the tile, with the gaps left transparent
+    Letterbox,
+}
+
+impl TileFit {
+    pub fn toggle(&self) -> Self {
+        match self {
+            Self::Stretch => Self::Crop,
+            Self::Crop => Self::Letterbox,
+            Self::Letterbox => Self::Stretch,
+        }
+    }
+}
+
+impl Display for TileFit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Stretch => write!(f, "Stretch"),
+            Self::Crop => write!(f, "Crop"),
+            Self::Letterbox => write!(f, "Letterbox"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum DistanceMetric {
+    /// Straight line distance in `distance_col`
+    Euclidean,
+    Ciede2000,
+    /// OkLab with lightness and chroma weighted separately
+    WeightedOkLab,
+}
+
+impl DistanceMetric {
+    pub fn toggle(&self) -> Self {
+        match self {
+            Self::Euclidean => Self::Ciede2000,
+            Self::Ciede2000 => Self::WeightedOkLab,
+            Self::WeightedOkLab => Self::Euclidean,
+        }
+    }
+}
+
+impl Display for DistanceMetric {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Euclidean => write!(f, "Euclidean"),
+            Self::Ciede2000 => write!(f, "CIEDE2000"),
+            Self::WeightedOkLab => write!(f, "Weighted Ok Lab"),
+        }
+    }
+}
+
+/// What translucent colours are composited over before being compared
+#[derive(Clone, Copy, PartialEq)]
+pub enum AlphaBackground {
+    Ignore,
+    White,
+    Black,
+    Custom([f32; 4]),
+}
+
+impl AlphaBackground {
+    pub fn toggle(&self) -> Self {
+        match self {
+            Self::Ignore => Self::White,
+            Self::White => Self::Black,
+            Self::Black | Self::Custom(_) => Self::Ignore,
+        }
+    }
+
+    pub fn col(&self) -> Option<[f32; 4]> {
+        match self {
+            Self::Ignore => None,
+            Self::White => Some([1.0; 4]),
+            Self::Black => Some([0.0, 0.0, 0.0, 1.0]),
+            Self::Custom(col) => Some(*col),
+        }
+    }
+}
+
+impl Display for AlphaBackground {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Ignore => write!(f, "Ignore alpha"),
+            Self::White => write!(f, "White"),
+            Self::Black => write!(f, "Black"),
+            Self::Custom(col) => write!(f, "{}", ColSelection::format_rgba(*col)),
+        }
+    }
+}
+
 #[derive(Clone, Copy)]
 pub struct PlaceSettings {
     pub distance_col: ColSelection,
+    pub metric: DistanceMetric,
+    pub lightness_weight: f32,
+    pub chroma_weight: f32,
+    pub background: AlphaBackground,
     pub temperature: f32,
     pub rect: Option<Rect>,
 }
 
+impl PlaceSettings {
+    /// How different two colours look, roughly from 0 to 1
+    pub fn distance(&self, col: [f32; 4], other: [f32; 4]) -> f32 {
+        let (col, other) = match self.background.col() {
+            Some(background) => (composite(col, background), composite(other, background)),
+            None => (col, other)
+        };
+
+        match self.metric {
+            DistanceMetric::Euclidean => self.distance_col.col_from_rgba_arr(col).distance(other),
+            DistanceMetric::Ciede2000 => ColSelection::CieLab.col_from_rgba_arr(col).distance(other),
+            DistanceMetric::WeightedOkLab => weighted_oklab_distance(col, other, self.lightness_weight, self.chroma_weight),
+        }
+    }
+}
+
+/// Blends `col` over an opaque `background` in linear light
+fn composite(col: [f32; 4], background: [f32; 4]) -> [f32; 4] {
+    let a = col[3];
+    let blend = |c: f32, b: f32| linear_to_srgb(srgb_to_linear(c) * a + srgb_to_linear(b) * (1.0 - a));
+
+    [blend(col[0], background[0]), blend(col[1], background[1]), blend(col[2], background[2]), 1.0]
+}
+
+const MAX_DEPTH_SLIDER: usize = 16;
+
 pub struct ExportSettingsNode {
 
 }
@@ -82,18 +290,74 @@ impl Node for ExportSettingsNode {
             process.changed_this_frame = true;
         }
 
+        match &mut process.tile_size {
+            TileSize::Fixed(size) => {
+                for (axis, label) in ["Tile width", "Tile height"].iter().enumerate() {
+                    if let Some(value) = slider(
+                        ENABLEDCOL,
+                        DISABLEDCOL,
+                        Rect::new(150.0, 280.0 + 60.0 * axis as f32, 300.0, 18.0),
+                        &format!("{label}: {}", size[axis]),
+                        size[axis] as f32,
+                        1.0,
+                        255.0,
+                        ctx.user_inputs,
+                        node
+                    ) {
+                        size[axis] = value.round() as u32;
+                        process.changed_this_frame = true;
+                    }
+                }
+            },
+            TileSize::Native => {
+                draw_text("Each texture is used at its own size.", 150.0, 300.0, 18.0, BLACK);
+            },
+        }
+
+        let native = process.tile_size == TileSize::Native;
+        if sub_ui_button(Rect::new(150.0, 380.0, 146.0, 38.0), if native { "Native size" } else { "Fixed size" }, DISABLEDCOL, DISABLEDHOVERCOL, node, ctx.user_inputs) {
+            process.tile_size = if native { TileSize::Fixed([128, 128]) } else { TileSize::Native };
+            process.changed_this_frame = true;
+        }
+        if native {
+            disabled_ui_button(Rect::new(304.0, 380.0, 146.0, 38.0), &format!("Fit: {}", process.fit), DISABLEDCOL);
+        } else if sub_ui_button(Rect::new(304.0, 380.0, 146.0, 38.0), &format!("Fit: {}", process.fit), DISABLEDCOL, DISABLEDHOVERCOL, node, ctx.user_inputs) {
+            process.fit = process.fit.toggle();
+            process.changed_this_frame = true;
+        }
+
+        if let Err(err) = process.validate() {
+            draw_text(&err, 150.0, 446.0, 18.0, RED);
+        }
+
+        // FOLDER
+        if sub_ui_button(Rect::new(150.0, 470.0, 146.0, 38.0), if process.tolerant { "Skip bad files" } else { "Stop on bad files" }, DISABLEDCOL, DISABLEDHOVERCOL, node, ctx.user_inputs) {
+            process.tolerant = !process.tolerant;
+            process.changed_this_frame = true;
+        }
+        if sub_ui_button(Rect::new(304.0, 470.0, 146.0, 38.0), if process.follow_symlinks { "Follow links" } else { "Skip links" }, DISABLEDCOL, DISABLEDHOVERCOL, node, ctx.user_inputs) {
+            process.follow_symlinks = !process.follow_symlinks;
+            process.changed_this_frame = true;
+        }
+
+        // The top of the slider means no limit
+        let depth = process.max_depth.unwrap_or(MAX_DEPTH_SLIDER);
         if let Some(value) = slider(
             ENABLEDCOL,
             DISABLEDCOL,
-            Rect::new(150.0, 280.0, 300.0, 18.0),
-            &format!("Pixel size: {}", process.pixel_size),
-            process.pixel_size as f32,
+            Rect::new(150.0, 550.0, 300.0, 18.0),
+            &match process.max_depth {
+                Some(depth) => format!("Subfolder depth: {depth}"),
+                None => "Subfolder depth: Unlimited".to_string(),
+            },
+            depth as f32,
             0.0,
-            256.0,
+            MAX_DEPTH_SLIDER as f32,
             ctx.user_inputs,
             node
         ) {
-            process.pixel_size = value as u32;
+            let value = value.round() as usize;
+            process.max_depth = (value < MAX_DEPTH_SLIDER).then_some(value);
             process.changed_this_frame = true;
         }
     }
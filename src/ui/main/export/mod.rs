@@ -4,9 +4,11 @@ use super::*;
 mod select_folder;
 use select_folder::SelectFolder;
 mod export_settings;
-use export_settings::{ExportSettings, ExportSettingsNode, ProcessSettings};
+use export_settings::{ExportSettings, ExportSettingsNode, ProcessSettings, TileSize, TileFit, PlacementMapFormat, DistanceMetric, AlphaBackground};
 mod preview;
-use preview::Preview;
+use preview::{Preview, Texture, is_archive};
+mod usage_report;
+use usage_report::{UsageReport, UsageReportNode, Placement, write_text};
 
 fn pick_folder(title: &str) -> Option<std::path::PathBuf> {
     FileDialog::new()
@@ -14,24 +16,39 @@ fn pick_folder(title: &str) -> Option<std::path::PathBuf> {
         .pick_folder()
 }
 
+fn pick_archive(title: &str) -> Option<std::path::PathBuf> {
+    FileDialog::new()
+        .set_title(title)
+        .add_filter("Zip archive", &["zip"])
+        .pick_file()
+}
+
 fn save_file(title: &str) -> Option<std::path::PathBuf> {
     FileDialog::new()
         .set_title(title)
         .save_file()
 }
 
+fn save_file_with_filter(title: &str, name: &str, extension: &str) -> Option<std::path::PathBuf> {
+    FileDialog::new()
+        .set_title(title)
+        .add_filter(name, &[extension])
+        .save_file()
+}
+
 #[derive(Default)]
 pub struct Export {
 }
 
-const TOPBAR: usize = 3;
-impl New for Export { // 0 is select folder, 1 is export settings, 2 is preview, 3 is topbar,
+const TOPBAR: usize = 4;
+impl New for Export { // 0 is select folder, 1 is export settings, 2 is preview, 3 is usage report, 4 is topbar,
     fn new(handler: &mut GenHandler) -> Self {
         status::push_nocheck::<1>(handler);
         
         handler.push_child::<SelectFolder>();
         handler.push_child::<ExportSettingsNode>();
         handler.push_child::<Preview>();
+        handler.push_child::<UsageReportNode>();
 
         handler.push_child_io::<Topbar<1>>((
             156.0,
@@ -40,6 +57,7 @@ impl New for Export { // 0 is select folder, 1 is export settings, 2 is preview,
                 "Select folder",
                 "Export settings",
                 "Preview",
+                "Usage report",
             ])
         ));
 
@@ -1,3 +1,5 @@
+use std::path::PathBuf;
+
 use process::LoaderStatus;
 mod process;
 
@@ -5,13 +7,13 @@ mod texture;
 use texture::RawTexture;
 
 use process::{LoaderWrapper, save_img};
+pub use process::is_archive;
+use colour_picker::{ColPicker, Picker};
 use macroquad::prelude::*;
 
 use super::*;
-// todo!() Add saving colours persistently and colour gradient thing
 // todo!() Add workspaces and importing from a file to automatically make the pixels. Make the current drawing an image
 // todo!() Add autosave and workspace settings.
-// todo!() Add ctrl + z and ctrl + y
 // todo!() Add settings for low memory usage or normal memory usage (load all textures into RAM or only resized ones)
 
 // How workspaces will work:
@@ -33,7 +35,8 @@ use super::*;
 //   shortcuts
 #[derive(Debug)]
 pub struct Texture {
-    texture: Texture2D,
+    pub path: PathBuf,
+    pub texture: Texture2D,
     average: [f32; 4],
     // noise: f32
 }
@@ -43,22 +46,27 @@ impl Texture {
     pub fn from_raw(texture: RawTexture) -> Self {
         Self {
             texture: Texture2D::from_rgba8(texture.width, texture.height, &texture.texture),
+            path: texture.path,
             average: texture.average
         }
     }
 }
 
+const SHOWN_WARNINGS: usize = 12;
+
 pub struct Preview {
     texture_loader: Option<LoaderWrapper>,
-    texture: Option<Texture2D>
+    texture: Option<Texture2D>,
+    show_warnings: bool
 }
 
 impl New for Preview {
     fn new(handler: &mut GenHandler) -> Self {
-        handler.push_data(ExportSettings::new(None, 0.0, ColSelection::OkLab, ColSelection::OkLab, 128, 1.0));
+        handler.push_data(ExportSettings::new(None, 0.0, ColSelection::OkLab, ColSelection::OkLab, TileSize::Fixed([128, 128]), 1.0));
         Self {
             texture_loader: None,
-            texture: None
+            texture: None,
+            show_warnings: false
         }
     }
 }
@@ -97,6 +105,30 @@ impl Preview {
                     dest_size: Some(vec2(target_width, target_height)),
                     ..Default::default()
                 });
+
+            // EYEDROPPER
+            let rect = Rect::new(400.0, 180.0, target_width, target_height);
+            if ctx.user_inputs.left_let_go &&
+                ctx.user_inputs.hover_test(node) &&
+                ctx.user_inputs.last_touch_test(node) &&
+                rect.contains(ctx.user_inputs.mouse) &&
+                rect.contains(ctx.user_inputs.lasttouch_mouse)
+            {
+                let image = texture.get_texture_data();
+                let pos = [
+                    ((ctx.user_inputs.mouse.x - rect.x) / rect.w * width) as i16,
+                    ((ctx.user_inputs.mouse.y - rect.y) / rect.h * height) as i16,
+                ];
+                let col = ctx.store.get::<EyedropperSettings>().sample(pos, |[x, y]| {
+                    (x >= 0 && y >= 0 && (x as u16) < image.width && (y as u16) < image.height).then(|| {
+                        let col = image.get_pixel(x as u32, y as u32);
+                        [col.r, col.g, col.b, col.a]
+                    })
+                });
+                if col.is_some() {
+                    ctx.store.get_mut::<Picker>().set_col(col);
+                }
+            }
         }
 
         match &mut self.texture_loader {
@@ -115,10 +147,11 @@ impl Preview {
                         ) {
                             println!("todo!(); HANDLE THIS ERROR CORRECTLY");
                             let settings = ctx.store.get::<ExportSettings>();
-                            self.texture_loader = Some(LoaderWrapper::with_folder(path.clone(), settings.process));
+                            self.texture_loader = Some(LoaderWrapper::with_source(path.clone(), &settings.archive_subpath, settings.process));
+                            self.show_warnings = false;
                         }
                     },
-                    None => disabled_ui_button(progress_rect, "Select a folder first.", DISABLEDCOL)
+                    None => disabled_ui_button(progress_rect, "Select a folder or archive first.", DISABLEDCOL)
                 }
             },
             Some(loader) => {
@@ -153,8 +186,40 @@ impl Preview {
                         ) &&
                             let Some(out_path) = save_file("Save as")
                         {
-                            loader.get_loader_mut().unwrap().export_png(&image.get_texture_data(), &out_path);
+                            let loader = loader.get_loader_mut().unwrap();
+                            loader.export_png(&image.get_texture_data(), &out_path);
+
+                            let format = ctx.store.get::<ExportSettings>().placement_map;
+                            if let Some(extension) = format.extension() &&
+                                let Some(map) = ctx.store.get::<UsageReport>().placement_map(format)
+                            {
+                                loader.export_text(&map, &out_path.with_extension(extension));
+                            }
                         };
+
+                        // SKIPPED FILES
+                        let warnings = loader.get_loader().unwrap().warnings();
+                        if !warnings.is_empty() {
+                            let label = format!("{} skipped", warnings.len());
+                            if sub_ui_button(Rect::new(710.0, 130.0, 120.0, 38.0), &label, DISABLEDCOL, DISABLEDHOVERCOL, node, ctx.user_inputs) {
+                                self.show_warnings = !self.show_warnings;
+                            }
+                        }
+                        if self.show_warnings && !warnings.is_empty() {
+                            let rect = Rect::new(400.0, 180.0, screen_width() - 500.0, screen_height() - 300.0);
+                            draw_rectangle(rect.x, rect.y, rect.w, rect.h, WHITE);
+                            draw_rectangle_lines(rect.x, rect.y, rect.w, rect.h, 2.0, DARKGRAY);
+
+                            let mut text = warnings.iter()
+                                .take(SHOWN_WARNINGS)
+                                .map(|warning| warning.replace("\n\n", " "))
+                                .collect::<Vec<_>>()
+                                .join("\n");
+                            if warnings.len() > SHOWN_WARNINGS {
+                                text.push_str(&format!("\n...and {} more.", warnings.len() - SHOWN_WARNINGS));
+                            }
+                            multiline_text(Rect::new(rect.x + 10.0, rect.y + 24.0, rect.w - 20.0, rect.h - 30.0), &text, 16);
+                        }
                     },
                     LoaderStatus::Loading { frac, current } => {
                         let inner = sub_ui_button(progress_rect, "", DISABLEDCOL, DISABLEDHOVERCOL, node, ctx.user_inputs);
@@ -201,6 +266,7 @@ impl Preview {
 
 impl Node for Preview {
     fn update(&mut self, ctx: &mut AppContextHandler, node: &NodeStore) {        
+        let picker_col = ctx.store.get_mut::<Picker>().get_col_rgba();
         let settings = ctx.store.get_mut::<ExportSettings>();
         let place_rect = settings.place.rect;
 
@@ -247,6 +313,73 @@ impl Node for Preview {
         }
 
 
+        if sub_ui_button(
+            Rect::new(50.0, 370.0, 300.0, 26.0), &format!("Distance metric: {}", settings.place.metric),
+            DISABLEDCOL,
+            DISABLEDHOVERCOL,
+            node,
+            ctx.user_inputs)
+        {
+            settings.place.metric = settings.place.metric.toggle();
+        }
+
+        if settings.place.metric == DistanceMetric::WeightedOkLab {
+            if let Some(value) = slider(
+                ENABLEDCOL,
+                DISABLEDCOL,
+                Rect::new(50.0, 430.0, 140.0, 18.0),
+                &format!("Lightness: {:.2}", settings.place.lightness_weight),
+                settings.place.lightness_weight,
+                0.0,
+                2.0,
+                ctx.user_inputs,
+                node
+            ) {
+                settings.place.lightness_weight = value;
+            }
+
+            if let Some(value) = slider(
+                ENABLEDCOL,
+                DISABLEDCOL,
+                Rect::new(210.0, 430.0, 140.0, 18.0),
+                &format!("Chroma: {:.2}", settings.place.chroma_weight),
+                settings.place.chroma_weight,
+                0.0,
+                2.0,
+                ctx.user_inputs,
+                node
+            ) {
+                settings.place.chroma_weight = value;
+            }
+        }
+
+        if sub_ui_button(
+            Rect::new(50.0, 470.0, 240.0, 26.0), &format!("Alpha background: {}", settings.place.background),
+            DISABLEDCOL,
+            DISABLEDHOVERCOL,
+            node,
+            ctx.user_inputs)
+        {
+            settings.place.background = settings.place.background.toggle();
+        }
+
+        // Lets translucent textures be matched against whatever they'll be shown over
+        if sub_ui_button(Rect::new(296.0, 470.0, 54.0, 26.0), "Pick", DISABLEDCOL, DISABLEDHOVERCOL, node, ctx.user_inputs) &&
+            let Some(col) = picker_col
+        {
+            settings.place.background = AlphaBackground::Custom(col);
+        }
+
+        if sub_ui_button(
+            Rect::new(50.0, 330.0, 300.0, 26.0), &format!("Placement map with image: {}", settings.placement_map),
+            DISABLEDCOL,
+            DISABLEDHOVERCOL,
+            node,
+            ctx.user_inputs)
+        {
+            settings.placement_map = settings.placement_map.toggle();
+        }
+
         let rect = Rect::new(50.0, 190.0, 300.0, 26.0);
         if let Some(Ok(loader)) = self.texture_loader.as_ref().map(|loader| loader.get_loader()) &&
             loader.is_loaded()
@@ -3,11 +3,15 @@ use super::*;
 
 use super::Texture;
 use std::f32;
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::Read;
 use std::path::Path;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::{path::PathBuf, thread};
 use crossbeam_channel::Sender;
 use crossbeam_channel::{Receiver, unbounded};
+use zip::ZipArchive;
 
 pub enum LoaderMsg {
     Progress {
@@ -16,10 +20,20 @@ pub enum LoaderMsg {
         current: String,
     },
     Image(RawTexture),
+    /// A file that was skipped, when loading tolerantly
+    Warning(String),
     Done,
     Error(String),
 }
 
+/// Where the files being loaded are read from
+#[derive(Clone)]
+enum Source {
+    Folder,
+    /// Every path is the name of a file inside this zip file
+    Archive(PathBuf),
+}
+
 #[derive(Debug)]
 pub enum LoaderStatus {
     Loading {
@@ -60,6 +74,7 @@ pub struct AsyncTextureLoader {
     status: LoaderStatus,
     textures: Vec<Texture>,
     loaded: usize,
+    warnings: Vec<String>,
 }
 
 pub struct LoaderWrapper {
@@ -67,9 +82,16 @@ pub struct LoaderWrapper {
 }
 
 impl LoaderWrapper {
-    pub fn with_folder(path: PathBuf, settings: ProcessSettings) -> Self {
+    /// Loads from a folder, or from inside a zip file, only under `subpath` if it isn't empty
+    pub fn with_source(path: PathBuf, subpath: &str, settings: ProcessSettings) -> Self {
+        let loader = if is_archive(&path) {
+            AsyncTextureLoader::with_archive(path, subpath, settings)
+        } else {
+            AsyncTextureLoader::with_folder(path, settings)
+        };
+
         Self {
-            loader: AsyncTextureLoader::with_folder(path, settings).map_err(LoaderStatus::GenError)
+            loader: loader.map_err(LoaderStatus::GenError)
         }
     }
 
@@ -91,52 +113,112 @@ impl LoaderWrapper {
 
 impl AsyncTextureLoader {
     fn with_folder(path: PathBuf, settings: ProcessSettings) -> Result<Self, String> {
-        let mut files = Vec::new();
-        let mut folders = Vec::new();
+        settings.validate()?;
 
-        folders.push(path);
+        let mut files = Vec::new();
+        let mut warnings = Vec::new();
+        // A problem is only a warning when loading tolerantly, otherwise it stops everything
+        let mut problem = |err: String| if settings.tolerant {
+            warnings.push(err);
+            Ok(())
+        } else {
+            Err(err)
+        };
+
+        // Followed links can lead back to a folder already seen, so real paths are remembered
+        let mut visited = HashSet::new();
+        // Skipping a link is what was asked for, so it's never an error, but it's still worth knowing about
+        let mut skipped_links = Vec::new();
+        if settings.follow_symlinks && let Ok(real) = path.canonicalize() {
+            visited.insert(real);
+        }
+        let mut folders = vec![(path, 0)];
 
-        while let Some(folder) = folders.pop() {
-            for path in match folder.read_dir() {
+        while let Some((folder, depth)) = folders.pop() {
+            let entries = match folder.read_dir() {
                 Ok(iter) => iter,
-                Err(err) => return Err(format!("Error reading folder `{}`:\n\n{err}", folder.to_string_lossy()))
-            } {
-                let path = match path {
-                    Ok(path) => path,
-                    Err(err) => return Err(format!("Error reading an item in folder `{}`:\n\n{err}", folder.to_string_lossy()))
+                Err(err) => {
+                    problem(format!("Error reading folder `{}`:\n\n{err}", folder.to_string_lossy()))?;
+                    continue;
+                }
+            };
+
+            for entry in entries {
+                let entry = match entry {
+                    Ok(entry) => entry,
+                    Err(err) => {
+                        problem(format!("Error reading an item in folder `{}`:\n\n{err}", folder.to_string_lossy()))?;
+                        continue;
+                    }
                 };
 
-                let path = path.path();
+                let path = entry.path();
+                let is_link = entry.file_type().is_ok_and(|kind| kind.is_symlink());
+                if is_link && !settings.follow_symlinks {
+                    skipped_links.push(format!("Skipped the link `{}`. Turn on following links to load it.", path.to_string_lossy()));
+                    continue;
+                }
 
+                // Both of these follow links
                 if path.is_dir() {
-                    folders.push(path);
+                    if settings.max_depth.is_some_and(|max| depth >= max) {
+                        continue;
+                    }
+                    if settings.follow_symlinks && !path.canonicalize().is_ok_and(|real| visited.insert(real)) {
+                        continue;
+                    }
+                    folders.push((path, depth + 1));
                 } else if path.is_file() {
                     if is_likely_image_file(&path) {
                         files.push(path);
                     }
                 } else {
-                    return Err(format!("Error reading item `{:?}`, likely due to permission errors.", path))
+                    problem(format!("Error reading item `{:?}`, likely due to permission errors or a broken link.", path))?;
                 }
             }
         }
 
-        Ok(Self::new(files, settings))
+        warnings.extend(skipped_links);
+        Ok(Self::new(files, Source::Folder, settings, warnings))
+    }
+
+    fn with_archive(path: PathBuf, subpath: &str, settings: ProcessSettings) -> Result<Self, String> {
+        settings.validate()?;
+
+        let archive = File::open(&path)
+            .map_err(|err| err.to_string())
+            .and_then(|file| ZipArchive::new(file).map_err(|err| err.to_string()))
+            .map_err(|err| format!("Error opening archive `{}`:\n\n{err}", path.display()))?;
+
+        let pattern = subpath.split('/').filter(|part| !part.is_empty()).collect::<Vec<_>>();
+        let names = archive.file_names()
+            .filter(|name| !name.ends_with('/'))
+            .filter(|name| is_likely_image_file(Path::new(name)))
+            .filter(|name| {
+                let parts = name.split('/').collect::<Vec<_>>();
+                // Folders below the subpath, not counting the file itself
+                let depth = parts.len().saturating_sub(pattern.len() + 1);
+                parts.len() > pattern.len() &&
+                    pattern.iter().zip(&parts).all(|(pattern, part)| *pattern == "*" || pattern == part) &&
+                    settings.max_depth.is_none_or(|max| depth <= max)
+            })
+            .map(PathBuf::from)
+            .collect::<Vec<_>>();
+
+        if names.is_empty() && !pattern.is_empty() {
+            return Err(format!("Couldn't find any images under `{subpath}` in archive `{}`.", path.display()));
+        }
+
+        Ok(Self::new(names, Source::Archive(path), settings, Vec::new()))
     }
 
-    fn new(paths: Vec<PathBuf>, process_settings: ProcessSettings) -> Self {
+    fn new(paths: Vec<PathBuf>, source: Source, process_settings: ProcessSettings, warnings: Vec<String>) -> Self {
         let (tx, rx) = unbounded();
         let cancel = CancelToken::new();
         let cancel_clone = cancel.clone();
 
         thread::spawn(move || {
-            load_images_parallel(
-                paths,
-                tx,
-                cancel_clone,
-                process_settings.averaging_col,
-                Some(process_settings.pixel_size),
-                process_settings.accept_transparent
-            );
+            load_images_parallel(paths, source, tx, cancel_clone, process_settings);
         });
 
         Self {
@@ -145,6 +227,7 @@ impl AsyncTextureLoader {
             status: LoaderStatus::Loading { frac: 0.0, current: "Initialising.".to_string() },
             textures: Vec::new(),
             loaded: 0,
+            warnings,
         }
     }
 
@@ -153,6 +236,11 @@ impl AsyncTextureLoader {
         self.cancel.cancel();
     }
 
+    /// Everything that was skipped while loading
+    pub fn warnings(&self) -> &[String] {
+        &self.warnings
+    }
+
     pub fn is_loaded(&self) -> bool {
         matches!(self.status, LoaderStatus::Done)
     }
@@ -166,9 +254,12 @@ impl AsyncTextureLoader {
                         self.status = LoaderStatus::Loading { frac: self.loaded as f32 / total as f32, current };
                     },
                     LoaderMsg::Image(texture) => self.textures.push(Texture::from_raw(texture)),
+                    LoaderMsg::Warning(warning) => self.warnings.push(warning),
                     LoaderMsg::Done => {
                         if self.textures.is_empty() {
                             self.status = LoaderStatus::GenError("Couldn't find any valid image files in that folder.".to_string());
+                        } else if let Some(err) = mismatched_size(&self.textures) {
+                            self.status = LoaderStatus::GenError(err);
                         } else {
                             self.status = LoaderStatus::Done;
                             break;
@@ -198,12 +289,18 @@ impl AsyncTextureLoader {
         }
     }
 
+    pub fn export_text(&mut self, text: &str, path: &Path) {
+        if let Err(err) = write_text(path, text) {
+            self.status = LoaderStatus::SaveError(err)
+        }
+    }
+
     pub fn generate_image(&self, store: &mut Store) -> Texture2D {
         generate_image(&self.textures, store)
     }
 }
 
-use image::{ImageBuffer, Rgba};
+use image::{DynamicImage, ImageBuffer, Rgba, RgbaImage};
 use macroquad::prelude::*;
 
 pub fn save_img(img: &Image, path: impl AsRef<std::path::Path>) -> Result<(), image::ImageError> {
@@ -220,11 +317,26 @@ pub fn save_img(img: &Image, path: impl AsRef<std::path::Path>) -> Result<(), im
     buffer.save(path)
 }
 
+/// Textures of different sizes can't share a grid, which only happens when they're used at their native size
+fn mismatched_size(textures: &[Texture]) -> Option<String> {
+    let first = textures.first()?;
+    let size = first.texture.size();
+    let other = textures.iter().find(|texture| texture.texture.size() != size)?;
+    let other_size = other.texture.size();
+
+    Some(format!(
+        "Native size needs every texture to be the same size, but `{}` is {} x {} and `{}` is {} x {}.\n\nPick a fixed tile size instead.",
+        first.path.display(), size.x, size.y,
+        other.path.display(), other_size.x, other_size.y
+    ))
+}
+
 fn generate_image(textures: &[Texture], store: &mut Store) -> Texture2D {
     let settings = store.get::<ExportSettings>();
-    let pixel_size = settings.process.pixel_size as f32;
-    let pixel_int = settings.process.pixel_size;
-    let col_sel = settings.place.distance_col;
+    // Every texture was checked to be this size when loaded
+    let tile = textures[0].texture.size();
+    let tile_int = [tile.x as u32, tile.y as u32];
+    let place = settings.place;
     let pixels = store.get::<PixelArray>();
     let rect = settings.place.rect.unwrap_or_else(|| {
         let [WorldPos(x, y), WorldPos(w, h)] = pixels.get_bounds();
@@ -234,10 +346,10 @@ fn generate_image(textures: &[Texture], store: &mut Store) -> Texture2D {
     let w = rect.w as u16;
     let h = rect.h as u16;
 
-    let target_w = w as f32 * pixel_size;
-    let target_h = h as f32 * pixel_size;
+    let target_w = w as f32 * tile.x;
+    let target_h = h as f32 * tile.y;
 
-    let render_target = render_target(w as u32 * pixel_int, h as u32 * pixel_int);
+    let render_target = render_target(w as u32 * tile_int[0], h as u32 * tile_int[1]);
     render_target.texture.set_filter(FilterMode::Nearest);
     
     set_camera(&Camera2D {
@@ -249,37 +361,37 @@ fn generate_image(textures: &[Texture], store: &mut Store) -> Texture2D {
     
     clear_background(BLANK);
 
+    let mut placements = Vec::new();
+
     if settings.place.temperature == 0.0 {
         for pixel in pixels.iter() {
             let x = pixel.pos[0] as f32 - rect.x;
             let y = pixel.pos[1] as f32 - rect.y;
-            let col = col_sel.col_from_rgba_arr(pixel.col);
-            let mut iter = textures.iter();
-            let mut best_texture = iter.next().unwrap();
-            let mut best_value = col.distance(best_texture.average);
-            for texture in iter {
-                let value = col.distance(texture.average);
+            let mut best_index = 0;
+            let mut best_value = place.distance(pixel.col, textures[0].average);
+            for (index, texture) in textures.iter().enumerate().skip(1) {
+                let value = place.distance(pixel.col, texture.average);
                 if value < best_value {
-                    best_texture = texture;
+                    best_index = index;
                     best_value = value;
                 }
             }
-            draw_texture(&best_texture.texture, x * pixel_size, y * pixel_size, WHITE);
+            draw_texture(&textures[best_index].texture, x * tile.x, y * tile.y, WHITE);
+            placements.push(Placement { pos: pixel.pos, texture: best_index });
         }
     } else {
         for pixel in pixels.iter() {
             let x = pixel.pos[0] as f32 - rect.x;
             let y = pixel.pos[1] as f32 - rect.y;
-            let col = col_sel.col_from_rgba_arr(pixel.col);
             let mut total = 0.0;
             let a = 1.0 - 1.0 / settings.place.temperature;
 
             let mut raw = Vec::new();
             for texture in textures {
-                let cost = col.distance(texture.average);
+                let cost = place.distance(pixel.col, texture.average);
                 let prob = (a * cost).exp();
                 total += prob;
-                raw.push((texture, prob));
+                raw.push(prob);
             }
 
             let scale = 1.0 / total;
@@ -287,24 +399,27 @@ fn generate_image(textures: &[Texture], store: &mut Store) -> Texture2D {
             let rand = rand::rand() as f32 / u32::MAX as f32;
             let mut cumulative = 0.0;
 
-            let mut selected_texture = None;
+            let mut selected_index = None;
 
-            for (texture, prob) in raw {
+            for (index, prob) in raw.into_iter().enumerate() {
                 cumulative += prob * scale;
                 if cumulative >= rand {
-                    selected_texture = Some(texture);
+                    selected_index = Some(index);
                     break;
                 }
             }
 
-            let selected_texture = selected_texture.unwrap_or_else(|| &textures[0]);
+            let selected_index = selected_index.unwrap_or(0);
 
-            draw_texture(&selected_texture.texture, x * pixel_size, y * pixel_size, WHITE);
+            draw_texture(&textures[selected_index].texture, x * tile.x, y * tile.y, WHITE);
+            placements.push(Placement { pos: pixel.pos, texture: selected_index });
         }
     }
 
     set_default_camera();
 
+    store.overwrite(UsageReport::new(textures, &placements, rect));
+
     render_target.texture
 }
 
@@ -322,61 +437,109 @@ fn is_likely_image_file(path: &Path) -> bool {
     }
 }
 
+pub fn is_archive(path: &Path) -> bool {
+    path.is_file() && path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("zip"))
+}
+
+fn read_file(source: &Source, archive: &mut Option<ZipArchive<File>>, path: &Path) -> Result<Vec<u8>, String> {
+    match source {
+        Source::Folder => std::fs::read(path).map_err(|err| err.to_string()),
+        Source::Archive(zip) => {
+            let Some(archive) = archive else {
+                return Err(format!("Couldn't open archive `{}`.", zip.display()));
+            };
+            let mut file = archive.by_name(&path.to_string_lossy()).map_err(|err| err.to_string())?;
+            let mut bytes = Vec::with_capacity(file.size() as usize);
+            file.read_to_end(&mut bytes).map_err(|err| err.to_string())?;
+            Ok(bytes)
+        }
+    }
+}
+
+/// Resizes `img` to exactly `size`, keeping its shape unless stretched
+fn fit_tile(img: &DynamicImage, size: [u32; 2], fit: TileFit) -> RgbaImage {
+    let [w, h] = size;
+    let filter = if w > img.width() || h > img.height() {
+        image::imageops::CatmullRom
+    } else {
+        image::imageops::Lanczos3
+    };
+
+    match fit {
+        TileFit::Stretch => img.resize_exact(w, h, filter).to_rgba8(),
+        TileFit::Crop => img.resize_to_fill(w, h, filter).to_rgba8(),
+        TileFit::Letterbox => {
+            let inner = img.resize(w, h, filter).to_rgba8();
+            let mut result = RgbaImage::new(w, h);
+            let x = w.saturating_sub(inner.width()) / 2;
+            let y = h.saturating_sub(inner.height()) / 2;
+            image::imageops::replace(&mut result, &inner, x as i64, y as i64);
+            result
+        }
+    }
+}
+
 fn load_images_parallel(
     paths: Vec<PathBuf>,
+    source: Source,
     tx: Sender<LoaderMsg>,
     cancel: CancelToken,
-    averaging_col: ColSelection,
-    pixel_size: Option<u32>,
-    accept_transparent: f32
+    settings: ProcessSettings
 ) {
     let total = paths.len();
+    let tile = match settings.tile_size {
+        TileSize::Fixed(size) => Some((size, settings.fit)),
+        TileSize::Native => None,
+    };
 
     let tx_clone = tx.clone();
 
     paths
         .into_par_iter()
-        .try_for_each(|path| {
+        .try_for_each_init(|| match &source {
+            // Each thread reads the archive through its own handle
+            Source::Archive(zip) => File::open(zip).ok().and_then(|file| ZipArchive::new(file).ok()),
+            Source::Folder => None,
+        }, |archive, path| {
             if cancel.is_cancelled() {
                 return Err(());
             }
 
-            let bytes = match std::fs::read(&path) {
+            let bytes = read_file(&source, archive, &path);
+            // Files in an archive are named as if the archive were a folder
+            let path = match &source {
+                Source::Archive(zip) => zip.join(path),
+                Source::Folder => path,
+            };
+
+            // A bad file is skipped with a warning when loading tolerantly, otherwise it stops everything
+            let fail = |err: String| if settings.tolerant {
+                let _ = tx_clone.send(LoaderMsg::Warning(err));
+                let _ = tx_clone.send(LoaderMsg::Progress { loaded: 1, total, current: path.display().to_string() });
+                Ok(())
+            } else {
+                let _ = tx_clone.send(LoaderMsg::Error(err));
+                Err(())
+            };
+
+            let bytes = match bytes {
                 Ok(bytes) => bytes,
-                Err(err) => {
-                    let _ = tx_clone.send(LoaderMsg::Error(format!("Error loading image `{}` from storage:\n\n{err}", path.display())));
-                    return Err(());
-                }
+                Err(err) => return fail(format!("Error loading image `{}` from storage:\n\n{err}", path.display()))
             };
 
             let img = match image::load_from_memory(&bytes) {
                 Ok(img) => img,
-                Err(err) => {
-                    let _ = tx_clone.send(LoaderMsg::Error(format!("Error making image `{}` from file:\n\n{err}", path.display())));
-                    return Err(());
-                }
+                Err(err) => return fail(format!("Error making image `{}` from file:\n\n{err}", path.display()))
             };
 
-            let (w, h, img) = if let Some(pixel_size) = pixel_size {
-                let filter = if pixel_size > img.width() || pixel_size > img.height() {
-                    image::imageops::CatmullRom
-                } else {
-                    image::imageops::Lanczos3
-                };
-    
-                (
-                    pixel_size,
-                    pixel_size,
-                    img.resize_exact(pixel_size, pixel_size, filter)
-                )
-            } else {
-                (img.width(), img.height(), img)
+            let img = match tile {
+                Some((size, fit)) => fit_tile(&img, size, fit),
+                None => img.to_rgba8(),
             };
+            let (w, h) = img.dimensions();
 
-            let img = img.to_rgba8();
-
-            let texture = RawTexture::new(w as u16, h as u16, img.into_raw(), averaging_col);
-            if texture.average[3] >= accept_transparent {
+            let texture = RawTexture::new(path.clone(), w as u16, h as u16, img.into_raw(), settings.averaging_col);
+            if texture.average[3] >= settings.accept_transparent {
                 let _ = tx_clone.send(LoaderMsg::Image(texture));
             }
 
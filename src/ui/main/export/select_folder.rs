@@ -3,39 +3,48 @@ use std::fs;
 
 use super::*;
 
+const MAX_RECENT: usize = 10;
+
 pub struct SelectFolder {
+    editing_subpath: bool,
 }
 
 impl New for SelectFolder {
     fn new(_handler: &mut GenHandler) -> Self {
         Self {
+            editing_subpath: false,
         }
     }
 }
 
 impl Node for SelectFolder {
     fn update(&mut self, ctx: &mut AppContextHandler, node: &NodeStore) {
-        let rect = Rect::new(28.0, 100.0, 150.0, 38.0);
         let settings = ctx.store.get_mut::<ExportSettings>();
         let selected_path = &mut settings.path;
         let changed = &mut settings.process.changed_this_frame;
 
-        draw_text("Changing this requires textures to be reloaded.", 220.0, 130.0, 18.0, BLACK);
-        if sub_ui_button(rect, "Select new folder", ENABLEDCOL, ENABLEDHOVERCOL, node, ctx.user_inputs) &&
-            let Some(folder) = pick_folder("Select a folder")
-        {
-            ctx.save_data.cached_dirs.retain(|d| *d != folder);
-            ctx.save_data.cached_dirs.push_front(folder);
-            if ctx.save_data.cached_dirs.len() > 10 {
-                ctx.save_data.cached_dirs.pop_back();
+        draw_text("Changing this requires textures to be reloaded.", 348.0, 130.0, 18.0, BLACK);
+        let picked = if sub_ui_button(Rect::new(28.0, 100.0, 150.0, 38.0), "Select new folder", ENABLEDCOL, ENABLEDHOVERCOL, node, ctx.user_inputs) {
+            pick_folder("Select a folder")
+        } else if sub_ui_button(Rect::new(188.0, 100.0, 150.0, 38.0), "Select zip archive", ENABLEDCOL, ENABLEDHOVERCOL, node, ctx.user_inputs) {
+            pick_archive("Select a zip archive")
+        } else {
+            None
+        };
+        // Folders and archives share the recent list
+        if let Some(source) = picked {
+            ctx.save_data.workspace.cached_dirs.retain(|d| *d != source);
+            ctx.save_data.workspace.cached_dirs.push_front(source);
+            if ctx.save_data.workspace.cached_dirs.len() > MAX_RECENT {
+                ctx.save_data.workspace.cached_dirs.pop_back();
             }
-            *selected_path = Some(ctx.save_data.cached_dirs.front().unwrap().clone());
+            *selected_path = Some(ctx.save_data.workspace.cached_dirs.front().unwrap().clone());
             *changed = true;
         }
 
         let rect = Rect::new(28.0, 150.0, screen_width() - 200.0, 28.0);
         
-        for (index, path) in ctx.save_data.cached_dirs.clone().iter().enumerate() {
+        for (index, path) in ctx.save_data.workspace.cached_dirs.clone().iter().enumerate() {
             let mut display: String = path.to_str().unwrap_or("This folder path cannot be displayed as it is not valid UTF-8").to_string();
             cut_text(&mut display, rect.w);
             if sub_ui_button(
@@ -46,14 +55,14 @@ impl Node for SelectFolder {
                 node,
                 ctx.user_inputs
             ) {
-                ctx.save_data.cached_dirs.remove(index);
-                ctx.save_data.cached_dirs.push_front(path.clone());
+                ctx.save_data.workspace.cached_dirs.remove(index);
+                ctx.save_data.workspace.cached_dirs.push_front(path.clone());
                 *selected_path = Some(path.clone());
                 *changed = true;
             }
             if sub_ui_button(
                 Rect::new(rect.w + 50.0, 150.0 + 38.0 * index as f32, 100.0, 28.0),
-                "Open folder",
+                if is_archive(path) { "Open archive" } else { "Open folder" },
                 DISABLEDCOL,
                 DISABLEDHOVERCOL,
                 node,
@@ -66,10 +75,62 @@ impl Node for SelectFolder {
                     eprintln!("Error opening folder `{path:?}`:\n{err}")
                 }
 
-                if let Err(err) = open(path) {
-                    eprintln!("Error opening folder `{path:?}`:\n{err}");
+                // Opening an archive would unzip it or open some other program, so show where it is instead
+                let folder = if is_archive(path) { path.parent().unwrap_or(path) } else { path.as_path() };
+                if let Err(err) = open(folder) {
+                    eprintln!("Error opening folder `{folder:?}`:\n{err}");
+                }
+            }
+        }
+
+        // SUBPATH
+        let settings = ctx.store.get_mut::<ExportSettings>();
+        if !settings.path.as_deref().is_some_and(is_archive) {
+            self.editing_subpath = false;
+            return;
+        }
+
+        let y = 160.0 + 38.0 * ctx.save_data.workspace.cached_dirs.len() as f32;
+        draw_text("Only load from this folder in the archive, * matches any folder:", 28.0, y + 10.0, 18.0, BLACK);
+        let mut display = if settings.archive_subpath.is_empty() && !self.editing_subpath {
+            "Whole archive, e.g. assets/*/textures/block".to_string()
+        } else {
+            settings.archive_subpath.clone()
+        };
+        if self.editing_subpath {
+            display.push('|');
+        }
+        let input_rect = Rect::new(28.0, y + 20.0, 400.0, 28.0);
+        if sub_ui_button(
+            input_rect,
+            &display,
+            if self.editing_subpath { ENABLEDCOL } else { DISABLEDCOL },
+            if self.editing_subpath { ENABLEDHOVERCOL } else { DISABLEDHOVERCOL },
+            node,
+            ctx.user_inputs
+        ) {
+            self.editing_subpath = true;
+            while get_char_pressed().is_some() {}
+        }
+
+        // The whole page is this node, so clicking anywhere off the box stops editing
+        if self.editing_subpath && ctx.user_inputs.left_mouse_pressed && !input_rect.contains(ctx.user_inputs.mouse) {
+            self.editing_subpath = false;
+        }
+        if self.editing_subpath {
+            ctx.user_inputs.disable_shortcuts();
+            while let Some(c) = get_char_pressed() {
+                if !c.is_control() {
+                    settings.archive_subpath.push(c);
+                    settings.process.changed_this_frame = true;
                 }
             }
+            if is_key_pressed(KeyCode::Backspace) && settings.archive_subpath.pop().is_some() {
+                settings.process.changed_this_frame = true;
+            }
+            if is_key_pressed(KeyCode::Enter) || is_key_pressed(KeyCode::Escape) {
+                self.editing_subpath = false;
+            }
         }
     }
 
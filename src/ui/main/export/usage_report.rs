@@ -0,0 +1,237 @@
+use std::{fs, path::{Path, PathBuf}};
+
+use serde::Serialize;
+use serde_json::to_string_pretty;
+
+use super::*;
+
+/// Which texture was drawn for a single pixel when generating the image.
+#[derive(Clone, Copy, Debug)]
+pub struct Placement {
+    pub pos: [i16; 2],
+    pub texture: usize
+}
+
+pub struct UsageEntry {
+    pub path: PathBuf,
+    pub thumbnail: Texture2D,
+    pub positions: Vec<[i16; 2]>
+}
+
+#[derive(Serialize)]
+struct UsageEntryStore {
+    path: String,
+    count: usize,
+    positions: Vec<[i16; 2]>
+}
+
+#[derive(Serialize)]
+struct PlacementMapStore {
+    origin: [i16; 2],
+    width: usize,
+    height: usize,
+    grid: Vec<Vec<Option<String>>>
+}
+
+/// How many times each texture was used in the last generated image, sorted from most to least used.
+#[derive(Default)]
+pub struct UsageReport {
+    pub entries: Vec<UsageEntry>,
+    pub rect: Rect
+}
+
+impl UsageReport {
+    pub fn new(textures: &[Texture], placements: &[Placement], rect: Rect) -> Self {
+        let mut positions = vec![Vec::new(); textures.len()];
+        for placement in placements {
+            positions[placement.texture].push(placement.pos);
+        }
+
+        let mut entries = textures
+            .iter()
+            .zip(positions)
+            .filter(|(_, positions)| !positions.is_empty())
+            .map(|(texture, positions)| UsageEntry {
+                path: texture.path.clone(),
+                thumbnail: texture.texture.clone(),
+                positions
+            })
+            .collect::<Vec<_>>();
+
+        entries.sort_by(|a, b| b.positions.len().cmp(&a.positions.len()).then_with(|| a.path.cmp(&b.path)));
+
+        Self { entries, rect }
+    }
+
+    /// Row major grid covering `rect`, holding the path of the texture drawn at each pixel.
+    fn placement_grid(&self) -> Vec<Vec<Option<String>>> {
+        let width = self.rect.w as usize;
+        let height = self.rect.h as usize;
+        let mut grid = vec![vec![None; width]; height];
+
+        for entry in &self.entries {
+            let path = entry.path.to_string_lossy().to_string();
+            for [x, y] in &entry.positions {
+                let x = *x as f32 - self.rect.x;
+                let y = *y as f32 - self.rect.y;
+                if x >= 0.0 && y >= 0.0 && (x as usize) < width && (y as usize) < height {
+                    grid[y as usize][x as usize] = Some(path.clone());
+                }
+            }
+        }
+
+        grid
+    }
+
+    pub fn placement_map(&self, format: PlacementMapFormat) -> Option<String> {
+        match format {
+            PlacementMapFormat::None => None,
+            PlacementMapFormat::Json => {
+                let grid = self.placement_grid();
+                Some(to_string_pretty(&PlacementMapStore {
+                    origin: [self.rect.x as i16, self.rect.y as i16],
+                    width: self.rect.w as usize,
+                    height: self.rect.h as usize,
+                    grid
+                }).unwrap())
+            },
+            PlacementMapFormat::Csv => {
+                let grid = self.placement_grid();
+                Some(grid.iter().map(|row| {
+                    row.iter().map(|path| path.as_deref().map(csv_field).unwrap_or_default()).collect::<Vec<_>>().join(",")
+                }).collect::<Vec<_>>().join("\n") + "\n")
+            }
+        }
+    }
+
+    pub fn total(&self) -> usize {
+        self.entries.iter().map(|entry| entry.positions.len()).sum()
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut result = String::from("path,count,positions\n");
+        for entry in &self.entries {
+            let positions = entry.positions.iter().map(|[x, y]| format!("{x} {y}")).collect::<Vec<_>>().join(";");
+            result.push_str(&format!("{},{},{}\n", csv_field(&entry.path.to_string_lossy()), entry.positions.len(), positions));
+        }
+        result
+    }
+
+    pub fn to_json(&self) -> String {
+        let entries = self.entries.iter().map(|entry| UsageEntryStore {
+            path: entry.path.to_string_lossy().to_string(),
+            count: entry.positions.len(),
+            positions: entry.positions.clone()
+        }).collect::<Vec<_>>();
+
+        to_string_pretty(&entries).unwrap()
+    }
+}
+
+pub fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+pub fn write_text(path: &Path, text: &str) -> Result<(), String> {
+    fs::write(path, text).map_err(|err| format!("Error writing `{}`:\n\n{err}", path.display()))
+}
+
+pub struct UsageReportNode {
+    scroll: f32,
+    error: Option<String>
+}
+
+impl New for UsageReportNode {
+    fn new(handler: &mut GenHandler) -> Self {
+        handler.push_data(UsageReport::default());
+
+        Self {
+            scroll: 0.0,
+            error: None
+        }
+    }
+}
+
+const ROW_HEIGHT: f32 = 34.0;
+
+impl Node for UsageReportNode {
+    fn update(&mut self, ctx: &mut AppContextHandler, node: &NodeStore) {
+        let report = ctx.store.get::<UsageReport>();
+
+        if report.entries.is_empty() {
+            disabled_ui_button(Rect::new(28.0, 100.0, 300.0, 38.0), "Generate a preview first.", DISABLEDCOL);
+            return;
+        }
+
+        let csv_rect = Rect::new(28.0, 100.0, 150.0, 38.0);
+        let json_rect = Rect::new(190.0, 100.0, 150.0, 38.0);
+
+        if sub_ui_button(csv_rect, "Export as CSV", ENABLEDCOL, ENABLEDHOVERCOL, node, ctx.user_inputs) &&
+            let Some(path) = save_file_with_filter("Save report as", "CSV", "csv")
+        {
+            self.error = write_text(&path, &report.to_csv()).err();
+        }
+
+        if sub_ui_button(json_rect, "Export as JSON", ENABLEDCOL, ENABLEDHOVERCOL, node, ctx.user_inputs) &&
+            let Some(path) = save_file_with_filter("Save report as", "JSON", "json")
+        {
+            self.error = write_text(&path, &report.to_json()).err();
+        }
+
+        draw_text(&format!("{} textures used for {} pixels.", report.entries.len(), report.total()), 360.0, 124.0, 18.0, BLACK);
+
+        if let Some(err) = &self.error {
+            multiline_text(Rect::new(28.0, 170.0, 300.0, 300.0), err, 18);
+            if sub_ui_button(Rect::new(28.0, 150.0, 300.0, 26.0), "Error saving report. Click to dismiss.", DISABLEDCOL, DISABLEDHOVERCOL, node, ctx.user_inputs) {
+                self.error = None;
+            }
+            return;
+        }
+
+        let list_rect = Rect::new(28.0, 150.0, screen_width() - 56.0, screen_height() - 170.0);
+        let max_scroll = (report.entries.len() as f32 * ROW_HEIGHT - list_rect.h).max(0.0);
+
+        if ctx.user_inputs.hover_test(node) && list_rect.contains(ctx.user_inputs.mouse) {
+            let (_, mut my) = mouse_wheel();
+            if cfg!(target_os = "windows") {
+                my /= 120.0;
+            }
+            self.scroll -= my * 20.0;
+        }
+        self.scroll = self.scroll.clamp(0.0, max_scroll);
+
+        let first = (self.scroll / ROW_HEIGHT) as usize;
+        let visible = (list_rect.h / ROW_HEIGHT) as usize + 2;
+
+        for (index, entry) in report.entries.iter().enumerate().skip(first).take(visible) {
+            let y = list_rect.y + index as f32 * ROW_HEIGHT - self.scroll;
+            if y + ROW_HEIGHT > list_rect.y + list_rect.h {
+                break;
+            }
+
+            draw_rectangle(list_rect.x, y, 28.0, 28.0, DISABLEDCOL);
+            draw_texture_ex(
+                &entry.thumbnail,
+                list_rect.x + 2.0,
+                y + 2.0,
+                WHITE,
+                DrawTextureParams {
+                    dest_size: Some(vec2(24.0, 24.0)),
+                    ..Default::default()
+                }
+            );
+
+            let mut display = format!("{} x {}", entry.positions.len(), entry.path.to_string_lossy());
+            cut_text(&mut display, list_rect.w - 36.0);
+            draw_text(&display, list_rect.x + 36.0, y + 19.0, 18.0, BLACK);
+        }
+    }
+
+    fn hit_detect(&mut self, pos: Vec2, node: &NodeStore, store: &mut Store) -> Vec<WeakNode> {
+        node.hit_detect_children_and_self(pos, store)
+    }
+}
@@ -5,12 +5,17 @@ mod topbar;
 mod draw;
 mod settings;
 mod export;
+mod palette;
 use serde::{Deserialize, Serialize};
 use topbar::{Topbar, status};
 use draw::Draw;
 use settings::Settings;
 use export::Export;
+use palette::PaletteTab;
 pub use draw::DrawState;
+use draw::EyedropperSettings;
+
+const IMAGE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "gif", "bmp", "tga", "tiff", "tif", "webp"];
 
 pub struct Main {
 }
@@ -19,7 +24,8 @@ pub struct Main {
 pub enum Tab {
     Draw,
     Settings,
-    Export
+    Export,
+    Palette
 }
 
 impl Display for Tab {
@@ -28,6 +34,7 @@ impl Display for Tab {
             Self::Draw => write!(f, "Draw"),
             Self::Settings => write!(f, "Settings"),
             Self::Export => write!(f, "Export"),
+            Self::Palette => write!(f, "Palette"),
         }
     }
 }
@@ -39,18 +46,20 @@ impl FromStr for Tab {
             "Draw" => Self::Draw,
             "Settings" => Self::Settings,
             "Export" => Self::Export,
+            "Palette" => Self::Palette,
             _ => return Err(())
         };
         Ok(result)
     }
 }
 
-const TOPBAR: usize = 3;
-impl New for Main { // 0 is draw, 1 is settings, 2 is export, 3 is topbar
+const TOPBAR: usize = 4;
+impl New for Main { // 0 is draw, 1 is settings, 2 is export, 3 is palette, 4 is topbar
     fn new(handler: &mut GenHandler) -> Self {
         handler.push_child::<Draw>();
         handler.push_child::<Settings>();
         handler.push_child::<Export>();
+        handler.push_child::<PaletteTab>();
 
         status::push::<0>(handler);
         handler.push_child_io::<Topbar<0>>((
@@ -60,6 +69,7 @@ impl New for Main { // 0 is draw, 1 is settings, 2 is export, 3 is topbar
                 "Draw",
                 "Settings",
                 "Export",
+                "Palette",
             ])
         ));
 
@@ -0,0 +1,72 @@
+use super::*;
+use formats::PaletteFormat;
+
+pub struct PaletteFiles {
+    message: Option<String>
+}
+
+impl New for PaletteFiles {
+    fn new(_handler: &mut GenHandler) -> Self {
+        Self {
+            message: None
+        }
+    }
+}
+
+impl PaletteFiles {
+    fn fill(&mut self, palette: &mut Palette, cols: Result<Vec<[f32; 4]>, String>) {
+        self.message = match cols {
+            Ok(cols) => {
+                let total = cols.len();
+                palette.set_cols(cols);
+                Some(format!("Loaded {total} colours."))
+            },
+            Err(err) => Some(err)
+        };
+    }
+}
+
+impl Node for PaletteFiles {
+    fn update(&mut self, ctx: &mut AppContextHandler, node: &NodeStore) {
+        let filters = PaletteFormat::ALL.map(|format| (format.name(), [format.extension()]));
+        let filters = filters.iter().map(|(name, ext)| (*name, &ext[..])).collect::<Vec<_>>();
+
+        if sub_ui_button(Rect::new(28.0, 100.0, 250.0, 38.0), "Import palette", ENABLEDCOL, ENABLEDHOVERCOL, node, ctx.user_inputs) &&
+            let Some(path) = pick_file("Import palette", &filters)
+        {
+            self.fill(&mut ctx.save_data.workspace.palette, formats::import(&path));
+        }
+
+        if sub_ui_button(Rect::new(28.0, 150.0, 250.0, 38.0), "Export palette", ENABLEDCOL, ENABLEDHOVERCOL, node, ctx.user_inputs) &&
+            let Some(mut path) = save_file("Export palette", &filters)
+        {
+            if PaletteFormat::from_path(&path).is_none() {
+                path.set_extension(PaletteFormat::Gimp.extension());
+            }
+            let cols = ctx.save_data.workspace.palette.cols().collect::<Vec<_>>();
+            self.message = Some(match formats::export(&path, &cols) {
+                Ok(()) => format!("Saved {} colours.", cols.len()),
+                Err(err) => err
+            });
+        }
+
+        if sub_ui_button(Rect::new(28.0, 200.0, 250.0, 38.0), "Extract palette from image", DISABLEDCOL, DISABLEDHOVERCOL, node, ctx.user_inputs) &&
+            let Some(path) = pick_file("Extract palette from image", &[("Image", IMAGE_EXTENSIONS)])
+        {
+            self.fill(&mut ctx.save_data.workspace.palette, formats::extract_from_image(&path));
+        }
+
+        if sub_ui_button(Rect::new(28.0, 250.0, 250.0, 38.0), "Extract palette from drawing", DISABLEDCOL, DISABLEDHOVERCOL, node, ctx.user_inputs) {
+            let cols = formats::extract(ctx.store.get::<PixelArray>().iter().map(|pixel| pixel.col));
+            self.fill(&mut ctx.save_data.workspace.palette, Ok(cols));
+        }
+
+        if let Some(message) = &self.message {
+            multiline_text(Rect::new(28.0, 320.0, 260.0, 300.0), message, 18);
+        }
+    }
+
+    fn hit_detect(&mut self, pos: Vec2, node: &NodeStore, store: &mut Store) -> Vec<WeakNode> {
+        node.hit_detect_children_and_self(pos, store)
+    }
+}
@@ -0,0 +1,49 @@
+use rfd::FileDialog;
+
+use super::*;
+mod files;
+mod ramp;
+mod swatches;
+use files::PaletteFiles;
+use ramp::{PaletteRamp, RampStops};
+use swatches::PaletteSwatches;
+
+fn pick_file(title: &str, filters: &[(&str, &[&str])]) -> Option<std::path::PathBuf> {
+    let mut dialog = FileDialog::new().set_title(title);
+    for (name, extensions) in filters {
+        dialog = dialog.add_filter(*name, extensions);
+    }
+    dialog.pick_file()
+}
+
+fn save_file(title: &str, filters: &[(&str, &[&str])]) -> Option<std::path::PathBuf> {
+    let mut dialog = FileDialog::new().set_title(title);
+    for (name, extensions) in filters {
+        dialog = dialog.add_filter(*name, extensions);
+    }
+    dialog.save_file()
+}
+
+pub struct PaletteTab;
+
+impl New for PaletteTab { // 0 is swatches, 1 is ramp, 2 is files
+    fn new(handler: &mut GenHandler) -> Self {
+        handler.push_child::<PaletteSwatches>();
+        handler.push_child::<PaletteRamp>();
+        handler.push_child::<PaletteFiles>();
+
+        Self
+    }
+}
+
+impl Node for PaletteTab {
+    fn update(&mut self, ctx: &mut AppContextHandler, node: &NodeStore) {
+        clear_background(WHITE);
+
+        node.update_children(ctx);
+    }
+
+    fn hit_detect(&mut self, pos: Vec2, node: &NodeStore, store: &mut Store) -> Vec<WeakNode> {
+        node.hit_detect_children_and_self(pos, store)
+    }
+}
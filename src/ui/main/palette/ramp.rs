@@ -0,0 +1,109 @@
+use super::*;
+
+const MAX_STOPS: usize = 9;
+
+/// Colours picked from the palette to run the ramp through, in order
+#[tuple_deref]
+pub struct RampStops(Vec<[f32; 4]>);
+
+impl RampStops {
+    pub fn toggle(&mut self, col: [f32; 4]) {
+        if let Some(index) = self.0.iter().position(|&d| d == col) {
+            self.0.remove(index);
+        } else if self.0.len() < MAX_STOPS {
+            self.0.push(col);
+        }
+    }
+}
+
+pub struct PaletteRamp {
+    steps: usize,
+    col_sel: ColSelection,
+    hue_shift: f32,
+}
+
+impl New for PaletteRamp {
+    fn new(handler: &mut GenHandler) -> Self {
+        handler.push_data(RampStops(vec![]));
+
+        Self {
+            steps: 5,
+            col_sel: ColSelection::OkLab,
+            hue_shift: 0.0,
+        }
+    }
+}
+
+impl Node for PaletteRamp {
+    fn update(&mut self, ctx: &mut AppContextHandler, node: &NodeStore) {
+        draw_text("Colour ramp", 300.0, 70.0, 18.0, BLACK);
+        multiline_text(Rect::new(300.0, 80.0, 220.0, 40.0), "Right click palette colours to add or remove stops", 16);
+
+        let stops = ctx.store.get::<RampStops>().to_vec();
+        for (index, col) in stops.iter().enumerate() {
+            let x = 300.0 + 24.0 * index as f32;
+            draw_rectangle(x, 120.0, 20.0, 20.0, arr_to_macroquad(*col));
+            draw_rectangle_lines(x, 120.0, 20.0, 20.0, 2.0, DISABLEDCOL);
+        }
+
+        if sub_ui_button(Rect::new(300.0, 150.0, 220.0, 28.0), "Clear stops", DISABLEDCOL, DISABLEDHOVERCOL, node, ctx.user_inputs) {
+            ctx.store.get_mut::<RampStops>().clear();
+        }
+
+        if let Some(value) = slider(
+            ENABLEDCOL,
+            DISABLEDCOL,
+            Rect::new(300.0, 210.0, 220.0, 18.0),
+            &format!("Steps: {}", self.steps),
+            self.steps as f32,
+            2.0,
+            30.0,
+            ctx.user_inputs,
+            node
+        ) {
+            self.steps = value.round() as usize;
+        }
+
+        if sub_ui_button(Rect::new(300.0, 245.0, 220.0, 28.0), &format!("Interpolate in: {}", self.col_sel), DISABLEDCOL, DISABLEDHOVERCOL, node, ctx.user_inputs) {
+            self.col_sel = self.col_sel.toggle();
+        }
+
+        if let Some(value) = slider(
+            ENABLEDCOL,
+            DISABLEDCOL,
+            Rect::new(300.0, 310.0, 220.0, 18.0),
+            &format!("Hue shift: {}°", self.hue_shift.round()),
+            self.hue_shift,
+            0.0,
+            60.0,
+            ctx.user_inputs,
+            node
+        ) {
+            self.hue_shift = value;
+        }
+
+        // PREVIEW
+        let cols = ramp(&stops, self.steps, self.col_sel, self.hue_shift);
+        if stops.len() >= 2 {
+            let width = 220.0 / cols.len() as f32;
+            for (index, col) in cols.iter().enumerate() {
+                draw_rectangle(300.0 + width * index as f32, 345.0, width, 28.0, arr_to_macroquad(*col));
+            }
+            draw_rectangle_lines(300.0, 345.0, 220.0, 28.0, 2.0, DISABLEDCOL);
+
+            if sub_ui_button(Rect::new(300.0, 385.0, 220.0, 28.0), "Add ramp to palette", ENABLEDCOL, ENABLEDHOVERCOL, node, ctx.user_inputs) {
+                ctx.save_data.workspace.palette.append(cols);
+            }
+        } else {
+            disabled_ui_button(Rect::new(300.0, 385.0, 220.0, 28.0), "Add ramp to palette", DISABLEDCOL);
+        }
+    }
+
+    fn hit_detect(&mut self, pos: Vec2, node: &NodeStore, _store: &mut Store) -> Vec<WeakNode> {
+        if Rect::new(300.0, 60.0, 220.0, 360.0).contains(pos) {
+            vec![node.get_weak()]
+        } else {
+            vec![]
+        }
+    }
+}
@@ -0,0 +1,120 @@
+use super::*;
+
+pub struct PaletteSwatches {
+    rect: Rect,
+    selected: Option<usize>,
+    first_row: usize,
+}
+
+impl New for PaletteSwatches {
+    fn new(_handler: &mut GenHandler) -> Self {
+        Self {
+            rect: Rect::default(),
+            selected: None,
+            first_row: 0,
+        }
+    }
+}
+
+impl Node for PaletteSwatches {
+    fn update(&mut self, ctx: &mut AppContextHandler, node: &NodeStore) {
+        let palette = &mut ctx.save_data.workspace.palette;
+        palette.fit_slots();
+
+        // Leaves room for the name input underneath
+        let rows = palette.saved_cols.len() / PALETTE_WIDTH;
+        let visible_rows = (((screen_height() - 150.0) / 33.0) as usize).clamp(1, rows);
+        self.rect = Rect::new(
+            screen_width() - 33.0 * PALETTE_WIDTH as f32 - 28.0,
+            80.0,
+            33.0 * PALETTE_WIDTH as f32,
+            33.0 * visible_rows as f32
+        );
+        draw_text("Current palette", self.rect.x, 70.0, 18.0, BLACK);
+
+        let hovered = ctx.user_inputs.hover_test(node);
+        let mouse = ctx.user_inputs.mouse;
+
+        // SCROLL
+        if hovered {
+            let (_, my) = mouse_wheel();
+            if my > 0.0 {
+                self.first_row = self.first_row.saturating_sub(1);
+            } else if my < 0.0 {
+                self.first_row += 1;
+            }
+        }
+        self.first_row = self.first_row.min(rows - visible_rows);
+        if rows > visible_rows {
+            draw_text(&format!("Rows {}-{} of {rows}, scroll for more", self.first_row + 1, self.first_row + visible_rows), self.rect.x - 100.0, self.rect.y + self.rect.h + 60.0, 16.0, GRAY);
+        }
+
+        let first = self.first_row * PALETTE_WIDTH;
+        for (index, swatch) in palette.saved_cols.iter().enumerate().skip(first).take(visible_rows * PALETTE_WIDTH) {
+            let rect = Rect::new(
+                self.rect.x + 33.0 * (index % PALETTE_WIDTH) as f32,
+                self.rect.y + 33.0 * (index / PALETTE_WIDTH - self.first_row) as f32,
+                28.0,
+                28.0
+            );
+
+            if col_button(
+                rect,
+                hovered && rect.contains(mouse),
+                ctx.user_inputs.left_let_go,
+                if self.selected == Some(index) { ENABLEDCOL } else { DISABLEDCOL },
+                if self.selected == Some(index) { ENABLEDHOVERCOL } else { DISABLEDHOVERCOL }
+            ) && swatch.is_some() {
+                self.selected = Some(index);
+                while get_char_pressed().is_some() {}
+            }
+
+            if let Some(swatch) = swatch {
+                if hovered && rect.contains(mouse) && ctx.user_inputs.right_let_go {
+                    ctx.store.get_mut::<RampStops>().toggle(swatch.col);
+                }
+
+                draw_rectangle(rect.x + 4.0, rect.y + 4.0, 20.0, 20.0, arr_to_macroquad(swatch.col));
+                if let Some(stop) = ctx.store.get::<RampStops>().iter().position(|&d| d == swatch.col) {
+                    draw_text(&(stop + 1).to_string(), rect.x + 9.0, rect.y + 20.0, 18.0, if ColSelection::OkLab.col_from_rgba_arr(swatch.col).to_wheel().2 > 0.6 { BLACK } else { WHITE });
+                }
+            }
+        }
+
+        if self.selected.is_some() && !ctx.user_inputs.last_touch_test(node) {
+            self.selected = None;
+        }
+
+        // NAME INPUT
+        let text_y = self.rect.y + self.rect.h + 20.0;
+        if let Some(index) = self.selected && let Some(Some(swatch)) = palette.saved_cols.get_mut(index) {
+            ctx.user_inputs.disable_shortcuts();
+            while let Some(c) = get_char_pressed() {
+                if !c.is_control() && swatch.name.chars().count() < 32 {
+                    swatch.name.push(c);
+                }
+            }
+
+            if is_key_pressed(KeyCode::Backspace) {
+                swatch.name.pop();
+            }
+            if is_key_pressed(KeyCode::Enter) || is_key_pressed(KeyCode::Escape) {
+                self.selected = None;
+            }
+
+            draw_text("Name (type to edit):", self.rect.x - 100.0, text_y, 18.0, BLACK);
+            draw_text(&format!("{}|", swatch.name), self.rect.x - 100.0, text_y + 22.0, 18.0, BLACK);
+        } else {
+            self.selected = None;
+            draw_text("Click a colour to name it", self.rect.x - 100.0, text_y, 18.0, GRAY);
+        }
+    }
+
+    fn hit_detect(&mut self, pos: Vec2, node: &NodeStore, _store: &mut Store) -> Vec<WeakNode> {
+        if self.rect.contains(pos) {
+            vec![node.get_weak()]
+        } else {
+            vec![]
+        }
+    }
+}
@@ -10,6 +10,7 @@ use super::colour_picker;
 use super::colour;
 pub use super::transform::*;
 pub use super::colour::*;
+pub use super::palette::*;
 pub use super::node::*;
 pub use super::helpers::*;
 pub mod main;